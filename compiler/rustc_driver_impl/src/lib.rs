@@ -452,6 +452,10 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.opts.unstable_opts.print_gc_finalizers {
+                sess.code_stats.print_gc_finalizers();
+            }
+
             if sess.opts.unstable_opts.print_vtable_sizes {
                 let crate_name = queries.global_ctxt()?.enter(|tcx| tcx.crate_name(LOCAL_CRATE));
 
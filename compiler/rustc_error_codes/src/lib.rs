@@ -534,6 +534,8 @@ macro_rules! error_codes {
 E0795: 0795,
 E0796: 0796,
 E0797: 0797,
+E0798: 0798,
+E0799: 0799,
         );
     )
 }
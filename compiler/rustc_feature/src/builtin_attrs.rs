@@ -486,6 +486,10 @@ pub struct BuiltinAttribute {
         EncodeCrossCrate::No, optimize_attribute, experimental!(optimize)
     ),
 
+    gated!(
+        finalizer_optional, Normal, template!(Word), WarnFollowing,
+        EncodeCrossCrate::Yes, experimental!(finalizer_optional)
+    ),
     gated!(
         ffi_pure, Normal, template!(Word), WarnFollowing,
         EncodeCrossCrate::No, experimental!(ffi_pure)
@@ -933,6 +937,12 @@ pub struct BuiltinAttribute {
         rustc_intrinsic_must_be_overridden, Normal, template!(Word), ErrorFollowing, EncodeCrossCrate::Yes,
         "the `#[rustc_intrinsic_must_be_overridden]` attribute is used to declare intrinsics without real bodies",
     ),
+    rustc_attr!(
+        rustc_gc_constructor, Normal, template!(Word, List: "unsynchronised|unfinalized|local"),
+        ErrorFollowing, EncodeCrossCrate::Yes,
+        "the `#[rustc_gc_constructor]` attribute is used to mark functions which move a value \
+        into a `Gc`, so that the value is checked by finalizer safety analysis",
+    ),
 
     // ==========================================================================
     // Internal attributes, Testing:
@@ -475,6 +475,10 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, ffi_const, "1.45.0", Some(58328)),
     /// Allows the use of `#[ffi_pure]` on foreign functions.
     (unstable, ffi_pure, "1.45.0", Some(58329)),
+    /// Allows using `#[finalizer_optional]` to have the compiler check that a
+    /// type's `Drop` impl only frees memory, so that it needs a finalizer only
+    /// if one of its components does.
+    (unstable, finalizer_optional, "CURRENT_RUSTC_VERSION", None),
     /// Allows using `#[repr(align(...))]` on function items
     (unstable, fn_align, "1.53.0", Some(82232)),
     /// Support delegating implementation of functions to other already implemented functions.
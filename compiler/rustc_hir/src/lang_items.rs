@@ -216,6 +216,8 @@ pub fn extract(attrs: &[ast::Attribute]) -> Option<(Symbol, Span)> {
     FnOnceOutput,            sym::fn_once_output,      fn_once_output,             Target::AssocTy,        GenericRequirement::None;
 
     FinalizerOptional,       sym::finalizer_optional,  finalizer_optional_trait,   Target::Trait,          GenericRequirement::Exact(0);
+    Finalize,                sym::finalize,            finalize_trait,             Target::Trait,          GenericRequirement::Exact(0);
+    Unfinalized,             sym::unfinalized,         unfinalized_trait,          Target::Trait,          GenericRequirement::Exact(0);
     Iterator,                sym::iterator,            iterator_trait,             Target::Trait,          GenericRequirement::Exact(0);
     FusedIterator,           sym::fused_iterator,      fused_iterator_trait,       Target::Trait,          GenericRequirement::Exact(0);
     Future,                  sym::future_trait,        future_trait,               Target::Trait,          GenericRequirement::Exact(0);
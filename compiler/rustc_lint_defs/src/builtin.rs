@@ -47,6 +47,7 @@
         FORBIDDEN_LINT_GROUPS,
         FUNCTION_ITEM_REFERENCES,
         FUZZY_PROVENANCE_CASTS,
+        GC_DANGLING_REFERENCE,
         HIDDEN_GLOB_REEXPORTS,
         ILL_FORMED_ATTRIBUTE_INPUT,
         INCOMPLETE_INCLUDE,
@@ -113,6 +114,7 @@
         UNREACHABLE_CODE,
         UNREACHABLE_PATTERNS,
         UNSAFE_OP_IN_UNSAFE_FN,
+        UNSOUND_FINALIZER,
         UNSTABLE_NAME_COLLISIONS,
         UNSTABLE_SYNTAX_PRE_EXPANSION,
         UNSUPPORTED_CALLING_CONVENTIONS,
@@ -4741,3 +4743,83 @@
     };
     crate_level_only
 }
+
+declare_lint! {
+    /// The `unsound_finalizer` lint detects values moved into a `Gc` whose
+    /// drop method cannot be safely run as a finalizer.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// struct HasGc(Gc<u8>);
+    ///
+    /// impl Drop for HasGc {
+    ///     fn drop(&mut self) {
+    ///         println!("{}", self.0);
+    ///     }
+    /// }
+    ///
+    /// let _ = Gc::new(HasGc(Gc::new(1)));
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Finalizers run on a separate thread and in no particular order, so a
+    /// drop method which runs as a finalizer must not dereference other `Gc`s,
+    /// and must only use values whose types implement `Send + Sync +
+    /// FinalizerSafe`. Doing otherwise is undefined behavior. This lint can be
+    /// lowered to a warning while migrating existing code, but every instance
+    /// of it is a potential soundness bug.
+    ///
+    /// Like any other lint, it is capped by `--cap-lints`, so it is silenced
+    /// in dependencies which Cargo builds with `--cap-lints allow`. Calls in
+    /// generic code are checked for each instantiation during
+    /// monomorphization, at the lint level of the generic code. Since this
+    /// happens after lint expectations are checked, an `#[expect]` which
+    /// applies to such a call is always considered fulfilled.
+    pub UNSOUND_FINALIZER,
+    Deny,
+    "detects values in a `Gc` whose drop methods cannot be safely finalized",
+}
+
+declare_lint! {
+    /// The `gc_dangling_reference` lint detects values containing references
+    /// which are moved into a `Gc` that will finalize them.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// struct HasRef<'a>(&'a mut Vec<u8>);
+    ///
+    /// impl Drop for HasRef<'_> {
+    ///     fn drop(&mut self) {}
+    /// }
+    ///
+    /// let mut v = Vec::new();
+    /// let _ = Gc::new(HasRef(&mut v));
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// A `Gc` may be finalized long after the data behind a reference it
+    /// contains has gone away, so its finalizer could observe a dangling
+    /// reference. References written as `&'static` in the type of a field are
+    /// allowed, since they can never dangle.
+    ///
+    /// As with `unsound_finalizer`, this lint is silenced in dependencies which
+    /// are built with `--cap-lints allow`, and an `#[expect]` which applies to a
+    /// call in generic code is always considered fulfilled.
+    pub GC_DANGLING_REFERENCE,
+    Deny,
+    "detects references in a `Gc` which may dangle when it is finalized",
+}
@@ -2,7 +2,7 @@
 use crate::rmeta::*;
 
 use rustc_ast::Attribute;
-use rustc_data_structures::fx::FxIndexSet;
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
 use rustc_data_structures::memmap::{Mmap, MmapMut};
 use rustc_data_structures::sync::{join, par_for_each_in, Lrc};
 use rustc_data_structures::temp_dir::MaybeTempDir;
@@ -18,7 +18,7 @@
 use rustc_middle::traits::specialization_graph;
 use rustc_middle::ty::codec::TyEncoder;
 use rustc_middle::ty::fast_reject::{self, TreatParams};
-use rustc_middle::ty::{AssocItemContainer, SymbolName};
+use rustc_middle::ty::{AssocItemContainer, SymbolName, TypeVisitableExt};
 use rustc_middle::util::common::to_readable_str;
 use rustc_serialize::{opaque, Decodable, Decoder, Encodable, Encoder};
 use rustc_session::config::{CrateType, OptLevel};
@@ -1978,6 +1978,37 @@ fn encode_traits(&mut self) -> LazyArray<DefIndex> {
         self.lazy_array(self.tcx.traits(LOCAL_CRATE).iter().map(|def_id| def_id.index))
     }
 
+    /// Records the summaries of the non-generic functions in this crate which
+    /// a finalizer passes the object being finalized to, and of those which
+    /// they pass it on to in turn. Downstream crates need these to follow such
+    /// calls, since the MIR of these functions is usually not encoded.
+    fn encode_finalizer_callee_uses(
+        &mut self,
+        uses: &[mir::FinalizerUse<'tcx>],
+        summarized: &mut FxHashSet<LocalDefId>,
+    ) {
+        let tcx = self.tcx;
+        let mut queue = uses.to_vec();
+        while let Some(finalizer_use) = queue.pop() {
+            let mir::FinalizerUse::CallArg { func, .. } = finalizer_use else {
+                continue;
+            };
+            let ty::FnDef(callee, args) = *func.kind() else {
+                continue;
+            };
+            let Some(callee) = callee.as_local() else {
+                continue;
+            };
+            if args.has_non_region_param() || !summarized.insert(callee) {
+                continue;
+            }
+            if let Some(callee_uses) = tcx.finalizer_uses(callee.to_def_id()) {
+                record_array!(self.tables.finalizer_uses[callee.to_def_id()] <- callee_uses);
+                queue.extend_from_slice(callee_uses);
+            }
+        }
+    }
+
     /// Encodes an index, mapping each trait to its (local) implementations.
     #[instrument(level = "debug", skip(self))]
     fn encode_impls(&mut self) -> LazyArray<TraitImpls> {
@@ -1985,6 +2016,7 @@ fn encode_impls(&mut self) -> LazyArray<TraitImpls> {
         let tcx = self.tcx;
         let mut fx_hash_map: FxHashMap<DefId, Vec<(DefIndex, Option<SimplifiedType>)>> =
             FxHashMap::default();
+        let mut summarized_callees = FxHashSet::default();
 
         for id in tcx.hir().items() {
             let DefKind::Impl { of_trait } = tcx.def_kind(id.owner_id) else {
@@ -2021,6 +2053,19 @@ fn encode_impls(&mut self) -> LazyArray<TraitImpls> {
                     let coerce_unsized_info = tcx.coerce_unsized_info(def_id).unwrap();
                     record!(self.tables.coerce_unsized_info[def_id] <- coerce_unsized_info);
                 }
+
+                // if this is an impl of `Drop` or `Finalize`, summarize how its
+                // method uses the object being finalized, so that downstream
+                // crates can check it without needing its MIR
+                let lang_items = tcx.lang_items();
+                if (Some(trait_ref.def_id) == lang_items.drop_trait()
+                    || Some(trait_ref.def_id) == lang_items.finalize_trait())
+                    && tcx.get_diagnostic_item(sym::gc).is_some()
+                    && let Some(uses) = tcx.finalizer_uses(def_id)
+                {
+                    record_array!(self.tables.finalizer_uses[def_id] <- uses);
+                    self.encode_finalizer_callee_uses(uses, &mut summarized_callees);
+                }
             }
         }
 
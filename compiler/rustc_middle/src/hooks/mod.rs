@@ -102,6 +102,11 @@ fn clone(&self) -> Self { *self }
 
     /// Create a list-like THIR representation for debugging.
     hook thir_flat(key: LocalDefId) -> String;
+
+    /// Runs finalizer safety analysis on a monomorphized Gc constructor call
+    /// from a generic body. The span of `tcx.at(..)` should be where the body
+    /// was instantiated.
+    hook check_mono_gc_constructor_call(call: mir::GcConstructorCall<'tcx>) -> ();
 }
 
 #[cold]
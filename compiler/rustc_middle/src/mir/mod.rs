@@ -321,6 +321,47 @@ pub enum MentionedItem<'tcx> {
     Closure(Ty<'tcx>),
 }
 
+/// How a function marked with `#[rustc_gc_constructor]` finalizes the value
+/// that it moves into a `Gc`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, HashStable, TyEncodable, TyDecodable)]
+pub enum GcConstructorKind {
+    /// The value is finalized on a separate finalizer thread. This is the
+    /// default, and is fully checked.
+    Checked,
+    /// The value is finalized on the thread which allocated it, so its
+    /// finalizer does not need to be `Send` or `Sync`.
+    Local,
+    /// The caller has promised that the finalizer does not race with other
+    /// threads (e.g. `Gc::new_unsynchronised`), so it does not need to be
+    /// `Sync`. Everything else is checked as for `Checked`.
+    Unsynchronised,
+    /// The value is never finalized (e.g. `Gc::new_unfinalizable`), so there
+    /// is nothing to check.
+    Unfinalized,
+}
+
+/// A call to a Gc constructor whose value type mentions generic parameters.
+///
+/// Finalizer safety analysis can only check such a call against the bounds in
+/// scope, so it is checked again for each concrete instantiation during
+/// monomorphization.
+#[derive(Clone, Debug, HashStable, TyEncodable, TyDecodable, TypeFoldable, TypeVisitable)]
+pub struct GcConstructorCall<'tcx> {
+    /// The type of the value which is moved into the `Gc`.
+    pub value_ty: Ty<'tcx>,
+    /// For each trait object in the value, the concrete types which were
+    /// unsized into it, as `(dyn_ty, concrete_ty)` pairs.
+    pub unsized_sources: Vec<(Ty<'tcx>, Ty<'tcx>)>,
+    pub kind: GcConstructorKind,
+    /// The span of the call to the constructor.
+    pub ctor: Span,
+    /// The span of the value which is moved into the `Gc`.
+    pub arg: Span,
+    /// The node whose lint levels apply to the call. This is cleared for calls
+    /// from other crates, which are linted at the level of the crate root.
+    pub lint_root: ClearCrossCrate<HirId>,
+}
+
 /// The lowered representation of a single function.
 #[derive(Clone, TyEncodable, TyDecodable, Debug, HashStable, TypeFoldable, TypeVisitable)]
 pub struct Body<'tcx> {
@@ -402,6 +443,11 @@ pub struct Body<'tcx> {
     /// `compiler/rustc_monomorphize/src/collector.rs` for more context.
     pub mentioned_items: Vec<Spanned<MentionedItem<'tcx>>>,
 
+    /// Calls to Gc constructors in this body which could not be fully checked
+    /// by finalizer safety analysis because they are generic. These are
+    /// re-checked by the collector for each instantiation of the body.
+    pub gc_constructor_calls: Vec<GcConstructorCall<'tcx>>,
+
     /// Does this body use generic parameters. This is used for the `ConstEvaluatable` check.
     ///
     /// Note that this does not actually mean that this body is not computable right now.
@@ -479,6 +525,7 @@ pub fn new(
             span,
             required_consts: Vec::new(),
             mentioned_items: Vec::new(),
+            gc_constructor_calls: Vec::new(),
             is_polymorphic: false,
             injection_phase: None,
             tainted_by_errors,
@@ -509,6 +556,7 @@ pub fn new_cfg_only(basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>) ->
             span: DUMMY_SP,
             required_consts: Vec::new(),
             mentioned_items: Vec::new(),
+            gc_constructor_calls: Vec::new(),
             var_debug_info: Vec::new(),
             is_polymorphic: false,
             injection_phase: None,
@@ -366,3 +366,35 @@ pub struct CoverageIdsInfo {
     /// set the `bytemap-bytes` argument of the `llvm.instrprof.mcdc.tvbitmap.update` intrinsic.
     pub mcdc_bitmap_bytes: u32,
 }
+
+/// A use of the object being finalized in the `drop` (or `finalize`) method of
+/// a `Drop` (or `Finalize`) impl, as found by finalizer safety analysis. These
+/// are recorded against the impl's generics, so that they can be checked for
+/// each type which the impl is used for.
+///
+/// Used by the `finalizer_uses` query.
+#[derive(Copy, Clone, PartialEq, Debug, TyEncodable, TyDecodable, HashStable)]
+pub enum FinalizerUse<'tcx> {
+    /// A field of type `ty` is projected out of a value derived from the
+    /// object being finalized.
+    Field { ty: Ty<'tcx>, span: Span },
+    /// Argument `index` of a call to `func`, which has type `ty`, is derived
+    /// from the object being finalized. The derived arguments of a call are
+    /// recorded next to each other.
+    CallArg { func: Ty<'tcx>, index: u32, ty: Ty<'tcx>, span: Span },
+}
+
+/// A reason why a value cannot be safely finalized, as found by finalizer
+/// safety analysis.
+///
+/// Used by the `finalizer_safety` query.
+#[derive(Copy, Clone, PartialEq, Debug, HashStable)]
+pub enum FinalizerError<'tcx> {
+    /// The drop glue of `ty` can't be known, e.g. because it is a trait object
+    /// whose bounds don't guarantee that it can be safely finalized.
+    UnknownDrop(Ty<'tcx>),
+    /// A value of type `ty`, which is not safe to use in a finalizer, is used
+    /// at `span` in `method`. If this happens in a function which `method`
+    /// calls, `call_site` is where that call is made.
+    UnsafeUse { ty: Ty<'tcx>, span: Span, method: &'static str, call_site: Option<Span> },
+}
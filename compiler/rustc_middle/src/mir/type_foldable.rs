@@ -20,6 +20,7 @@
     SwitchTargets,
     CoroutineKind,
     CoroutineSavedLocal,
+    GcConstructorKind,
 }
 
 TrivialTypeTraversalImpls! {
@@ -584,3 +584,20 @@ fn ty_def_id(&self) -> Option<DefId> {
         }
     }
 }
+
+impl<'tcx> Key for (mir::GcConstructorKind, ty::ParamEnvAnd<'tcx, Ty<'tcx>>) {
+    type Cache<V> = DefaultCache<Self, V>;
+
+    // Just forward to `Ty<'tcx>`
+
+    fn default_span(&self, _: TyCtxt<'_>) -> Span {
+        DUMMY_SP
+    }
+
+    fn ty_def_id(&self) -> Option<DefId> {
+        match self.1.value.kind() {
+            ty::Adt(adt, _) => Some(adt.did()),
+            _ => None,
+        }
+    }
+}
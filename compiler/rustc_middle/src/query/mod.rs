@@ -1348,6 +1348,14 @@
     query finalizer_optional_raw(env: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
         desc { "computing whether `{}` contains types which might need finalizing", env.value }
     }
+    /// Query backing `Ty::is_finalize`.
+    query is_finalize_raw(env: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
+        desc { "computing whether `{}` is `Finalize`", env.value }
+    }
+    /// Query backing `Ty::is_unfinalized`.
+    query is_unfinalized_raw(env: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
+        desc { "computing whether `{}` is `Unfinalized`", env.value }
+    }
     /// Query backing `Ty::needs_drop`.
     query needs_drop_raw(env: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
         desc { "computing whether `{}` needs drop", env.value }
@@ -1356,6 +1364,30 @@
     query needs_finalizer_raw(env: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
         desc { "computing whether `{}` needs finalizer", env.value }
     }
+    /// Summarizes how the method of a `Drop` or `Finalize` impl uses the object
+    /// being finalized, for finalizer safety analysis. Returns `None` if the
+    /// method has no MIR to summarize.
+    ///
+    /// For a function, summarizes how it uses all of its arguments instead.
+    /// Crates record these in their metadata for each impl, and for the
+    /// non-generic functions which the impls pass the object to (directly or
+    /// not), since the MIR of those is usually not encoded. Generic functions
+    /// are not summarized, as their MIR is available to downstream crates.
+    ///
+    /// Local summaries are computed from the built MIR, so that checking a
+    /// function never needs the optimized MIR of another.
+    query finalizer_uses(key: DefId) -> Option<&'tcx [mir::FinalizerUse<'tcx>]> {
+        desc { |tcx| "summarizing how `{}` uses the value being finalized", tcx.def_path_str(key) }
+        cache_on_disk_if { key.is_local() }
+        separate_provide_extern
+    }
+    /// Checks whether the drop glue of a value which is moved into a `Gc` can
+    /// be safely run by a finalizer, returning the reasons why not.
+    query finalizer_safety(
+        key: (mir::GcConstructorKind, ty::ParamEnvAnd<'tcx, Ty<'tcx>>)
+    ) -> &'tcx [mir::FinalizerError<'tcx>] {
+        desc { "checking whether `{}` can be safely finalized", key.1.value }
+    }
     /// Query backing `Ty::has_significant_drop_raw`.
     query has_significant_drop_raw(env: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
         desc { "computing whether `{}` has a significant drop", env.value }
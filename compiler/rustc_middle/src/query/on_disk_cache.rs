@@ -809,6 +809,7 @@ fn decode(d: &mut CacheDecoder<'a, $tcx>) -> Self {
     rustc_span::def_id::LocalDefId,
     (rustc_middle::middle::exported_symbols::ExportedSymbol<'tcx>, rustc_middle::middle::exported_symbols::SymbolExportInfo),
     ty::DeducedParamAttrs,
+    rustc_middle::mir::FinalizerUse<'tcx>,
 }
 
 //- ENCODING -------------------------------------------------------------------
@@ -127,6 +127,7 @@ impl $crate::ty::ParameterizedOverTcx for $($fake_path)::+<'static> {
     crate::middle::exported_symbols::ExportedSymbol,
     crate::mir::Body,
     crate::mir::CoroutineLayout,
+    crate::mir::FinalizerUse,
     crate::mir::interpret::ConstAllocation,
     ty::Ty,
     ty::FnSig,
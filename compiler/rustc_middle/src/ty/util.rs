@@ -1235,10 +1235,47 @@ pub fn is_freeze(self, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>) -> bool
         self.is_trivially_freeze() || tcx.is_freeze_raw(param_env.and(self))
     }
 
+    /// Checks whether this type only needs a finalizer if one of its
+    /// components does, either because it is marked `#[finalizer_optional]`
+    /// or because it implements the `FinalizerOptional` trait.
     pub fn finalizer_optional(self, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>) -> bool {
+        if let ty::Adt(def, _) = self.kind()
+            && tcx.has_attr(def.did(), sym::finalizer_optional)
+        {
+            return true;
+        }
         tcx.finalizer_optional_raw(param_env.and(self))
     }
 
+    /// Checks whether values of this type implement the `Finalize` trait, in
+    /// which case `Gc` calls `Finalize::finalize` instead of running the
+    /// type's drop glue when it is collected.
+    ///
+    /// This must agree with the `FinalizeGlue` specialization in `std`, which
+    /// applies to unsized types such as trait objects as well.
+    pub fn is_finalize(self, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>) -> bool {
+        if tcx.lang_items().finalize_trait().is_none() {
+            return false;
+        }
+        match self.kind() {
+            // Only `core` could implement `Finalize` for these, and it doesn't.
+            ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) | ty::Float(_) | ty::Never | ty::Str => {
+                false
+            }
+            _ => tcx.is_finalize_raw(param_env.and(self)),
+        }
+    }
+
+    /// Checks whether values of this type implement the `Unfinalized` trait,
+    /// either because they never need a finalizer or because a where clause
+    /// in `param_env` says so.
+    pub fn is_unfinalized(self, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>) -> bool {
+        if tcx.lang_items().unfinalized_trait().is_none() {
+            return false;
+        }
+        tcx.is_unfinalized_raw(param_env.and(self))
+    }
+
     /// Fast path helper for testing if a type is `Freeze`.
     ///
     /// Returning true means the type is known to be `Freeze`. Returning
@@ -1417,6 +1454,78 @@ pub fn needs_finalizer(self, tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>) -
         }
     }
 
+    /// Explains why this type needs a finalizer, as a chain of steps from this
+    /// type down to the component type which forced it. This follows the rules
+    /// used by `needs_finalizer_raw`, taking the first component which needs a
+    /// finalizer at each step.
+    pub fn finalizer_reasons(
+        self,
+        tcx: TyCtxt<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+    ) -> Vec<String> {
+        let mut ty = self;
+        if ty.is_finalize(tcx, param_env) {
+            return vec![format!("`{ty}` implements `Finalize`")];
+        }
+
+        let needs_finalizer = |ty: Ty<'tcx>| ty.needs_finalizer(tcx, param_env);
+        let find = |tys: &mut dyn Iterator<Item = Ty<'tcx>>| tys.find(|&ty| needs_finalizer(ty));
+        let mut reasons = Vec::new();
+        let mut seen = FxHashSet::default();
+        while seen.insert(ty) {
+            let (reason, next) = match *ty.kind() {
+                ty::Adt(def, args) => {
+                    let finalizer_optional = ty.finalizer_optional(tcx, param_env);
+                    let field = || {
+                        def.all_fields().find_map(|field| {
+                            let field_ty = field.ty(tcx, args);
+                            let field_ty = tcx
+                                .try_normalize_erasing_regions(param_env, field_ty)
+                                .unwrap_or(field_ty);
+                            needs_finalizer(field_ty).then_some((field.name, field_ty))
+                        })
+                    };
+                    if def.destructor(tcx).is_some() && !finalizer_optional {
+                        (format!("`{ty}` implements `Drop`"), None)
+                    } else if finalizer_optional && let Some(arg) = find(&mut args.types()) {
+                        (format!("`{ty}` is `FinalizerOptional`, but owns a `{arg}`"), Some(arg))
+                    } else if let Some((name, field_ty)) = field() {
+                        (format!("`{ty}` has field `{name}`: `{field_ty}`"), Some(field_ty))
+                    } else {
+                        break;
+                    }
+                }
+                ty::Array(elem, _) | ty::Slice(elem) => {
+                    (format!("`{ty}` contains `{elem}`"), Some(elem))
+                }
+                ty::Tuple(tys) => match find(&mut tys.iter()) {
+                    Some(elem) => (format!("`{ty}` contains `{elem}`"), Some(elem)),
+                    None => break,
+                },
+                ty::Closure(_, args) => match find(&mut args.as_closure().upvar_tys().iter()) {
+                    Some(upvar) => (format!("`{ty}` captures `{upvar}`"), Some(upvar)),
+                    None => break,
+                },
+                ty::CoroutineClosure(_, args) => {
+                    match find(&mut args.as_coroutine_closure().upvar_tys().iter()) {
+                        Some(upvar) => (format!("`{ty}` captures `{upvar}`"), Some(upvar)),
+                        None => break,
+                    }
+                }
+                ty::Coroutine(..) => (format!("`{ty}` is a coroutine"), None),
+                ty::Dynamic(..) => (format!("`{ty}` is a trait object"), None),
+                ty::Foreign(..) => (format!("`{ty}` is an extern type"), None),
+                _ => (format!("`{ty}` may need a finalizer"), None),
+            };
+            reasons.push(reason);
+            match next {
+                Some(next) => ty = next,
+                None => break,
+            }
+        }
+        reasons
+    }
+
     /// Returns `true` if equality for this type is both reflexive and structural.
     ///
     /// Reflexive equality for a type is indicated by an `Eq` impl for that type.
@@ -57,6 +57,7 @@ pub(super) fn build_custom_mir<'tcx>(
         span,
         required_consts: Vec::new(),
         mentioned_items: Vec::new(),
+        gc_constructor_calls: Vec::new(),
         is_polymorphic: false,
         tainted_by_errors: None,
         injection_phase: None,
@@ -14,9 +14,65 @@ mir_transform_ffi_unwind_call = call to {$foreign ->
     *[false] function pointer
     } with FFI-unwind ABI
 
+mir_transform_finalizer_optional_asm = this uses inline assembly
+
+mir_transform_finalizer_optional_call = `{$callee}` may do more than free memory
+
+mir_transform_finalizer_optional_drop = `Drop` impl for `#[finalizer_optional]` type `{$adt}` may do more than free memory
+    .attr_label = `{$adt}` is marked `#[finalizer_optional]` here
+    .help = the `Drop` impl of a `#[finalizer_optional]` type may only free memory and drop the type's fields. If skipping it is known to be sound, use an `unsafe impl` of `FinalizerOptional` instead
+
+mir_transform_finalizer_optional_drop_value = this drops a `{$ty}`, which is not a field or type parameter of the type
+
+mir_transform_finalizer_optional_indirect_call = this calls a function pointer, which may do more than free memory
+
+mir_transform_finalizer_optional_write = this writes to memory which is not part of the type, and may be shared
+
 mir_transform_fn_item_ref = taking a reference to a function item does not give a function pointer
     .suggestion = cast `{$ident}` to obtain a function pointer
 
+mir_transform_gc_check_deferred = this value can only be checked once its type is fully known
+
+mir_transform_gc_ctor_instantiated = in this `Gc` constructor call
+
+mir_transform_gc_dangling_field = the reference in field `{$field}` of `{$adt}` is not `'static`
+
+mir_transform_gc_dangling_reference = {$arg_desc} cannot be safely constructed.
+    .label = contains a reference (&) which may no longer be valid when it is finalized.
+    .ctor_label = `Gc::new` requires that any references it contains are `'static`.
+
+mir_transform_gc_finalize_unchecked = if this value is known to be safe to finalize, wrap it in `FinalizeUnchecked`
+
+mir_transform_gc_finalizer_safe = if `{$ty}` is safe to use in a finalizer, implement `FinalizerSafe` for it
+
+mir_transform_gc_instantiated_here = the `Gc` constructor call is instantiated here
+
+mir_transform_gc_invalid_constructor_arg = invalid argument to `#[rustc_gc_constructor]`
+
+mir_transform_gc_new_unfinalizable = if this value does not need to be finalized, use `Gc::new_unfinalizable`
+
+mir_transform_gc_unknown_drop = {$arg_desc} cannot be safely finalized.
+    .label = has a drop method which cannot be safely finalized.
+    .ctor_label = this `Gc` constructor requires that it can be safely finalized.
+    .help = the drop method of a trait object can't be known from its type, so the trait objects in {$arg_desc} must have bounds which make them safe to finalize, such as `Send + Sync + FinalizerSafe`.
+
+mir_transform_gc_unsafe_use = {$arg_desc} cannot be safely finalized.
+    .label = has a drop method which cannot be safely finalized.
+    .use_label = {$is_gc ->
+        [true] caused by the expression here in `{$method}` because
+        *[false] caused by the expression in `{$method}` here because
+    }
+    .use_reason = {$is_gc ->
+        [true] it uses another `Gc` type.
+        *[false] it uses a type which is not safe to use in a finalizer.
+    }
+    .call_site_label = in this call from `{$method}`
+    .help = {$is_gc ->
+        [true] `Gc` finalizers are unordered, so this field may have already been dropped. It is not safe to dereference.
+        *[false] `Gc` runs finalizers on a separate thread, so drop methods
+            must only use values whose types implement `Send + Sync + FinalizerSafe`.
+    }
+
 mir_transform_must_not_suspend = {$pre}`{$def_path}`{$post} held across a suspend point, but should not be
     .label = the value is held across this suspend point
     .note = {$reason}
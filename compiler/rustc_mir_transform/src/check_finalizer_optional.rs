@@ -0,0 +1,271 @@
+//! Checks the `Drop` impls of types marked `#[finalizer_optional]`.
+//!
+//! A `#[finalizer_optional]` type only needs a finalizer if one of its
+//! components does, so its `Drop` impl may be skipped when it is owned by a
+//! `Gc`. This is only sound if the `Drop` impl does nothing observable beyond
+//! freeing memory and dropping the type's components, which is what this
+//! checks. In particular, it may only write to the object being dropped and to
+//! its own locals, since anything else (e.g. a shared reference count) may be
+//! observed by other values. Types which need more than this can still opt in
+//! with an `unsafe impl FinalizerOptional`.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_span::symbol::sym;
+
+use crate::{errors, MirLint};
+
+pub struct CheckFinalizerOptional;
+
+impl<'tcx> MirLint<'tcx> for CheckFinalizerOptional {
+    fn run_lint(&self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>) {
+        let def_id = body.source.def_id();
+        let Some(adt_ty) = finalizer_optional_drop_impl(tcx, def_id) else {
+            return;
+        };
+        let ty::Adt(adt_def, args) = *adt_ty.kind() else {
+            return;
+        };
+
+        let mut checker =
+            Checker { tcx, param_env: tcx.param_env(def_id), components: FxHashSet::default() };
+        let components = adt_def.all_fields().map(|field| field.ty(tcx, args)).chain(args.types());
+        checker.components = components.map(|ty| checker.normalize(ty)).collect();
+        let attr = tcx.get_attr(adt_def.did(), sym::finalizer_optional).unwrap().span;
+
+        let emit = |span, violation| {
+            let adt = adt_ty;
+            tcx.dcx().emit_err(errors::FinalizerOptionalDrop { span, adt, attr, violation });
+        };
+        for block in body.basic_blocks.iter() {
+            for statement in &block.statements {
+                if let Some(violation) = checker.check_statement(body, statement) {
+                    emit(statement.source_info.span, violation);
+                }
+            }
+            let terminator = block.terminator();
+            if let Some(violation) = checker.check_terminator(body, terminator) {
+                emit(terminator.source_info.span, violation);
+            }
+        }
+    }
+}
+
+/// If `def_id` is the `drop` method of a `Drop` impl for a type marked
+/// `#[finalizer_optional]`, returns that type.
+fn finalizer_optional_drop_impl(tcx: TyCtxt<'_>, def_id: DefId) -> Option<Ty<'_>> {
+    let impl_did = tcx.impl_of_method(def_id)?;
+    let trait_ref = tcx.impl_trait_ref(impl_did)?.instantiate_identity();
+    if tcx.lang_items().drop_trait() != Some(trait_ref.def_id) {
+        return None;
+    }
+    let self_ty = trait_ref.self_ty();
+    match self_ty.kind() {
+        ty::Adt(def, _) if tcx.has_attr(def.did(), sym::finalizer_optional) => Some(self_ty),
+        _ => None,
+    }
+}
+
+struct Checker<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    /// The field types and type parameters of the `#[finalizer_optional]`
+    /// type. Whether it needs a finalizer is decided by these, so they can be
+    /// dropped freely.
+    components: FxHashSet<Ty<'tcx>>,
+}
+
+impl<'tcx> Checker<'tcx> {
+    /// Writes are only allowed to the object being dropped and to locals.
+    /// Anything else, such as a reference count behind a raw pointer or a
+    /// `static mut`, may be shared with other values.
+    fn check_statement(
+        &self,
+        body: &Body<'tcx>,
+        statement: &Statement<'tcx>,
+    ) -> Option<errors::FinalizerOptionalViolation<'tcx>> {
+        let place = match &statement.kind {
+            StatementKind::Assign(box (place, _))
+            | StatementKind::SetDiscriminant { place: box place, .. }
+            | StatementKind::Deinit(box place) => *place,
+            _ => return None,
+        };
+        let span = statement.source_info.span;
+        (!self.is_owned_place(body, place, &mut FxHashSet::default()))
+            .then_some(errors::FinalizerOptionalViolation::Write { span })
+    }
+
+    fn check_terminator(
+        &self,
+        body: &Body<'tcx>,
+        terminator: &Terminator<'tcx>,
+    ) -> Option<errors::FinalizerOptionalViolation<'tcx>> {
+        let span = terminator.source_info.span;
+        match &terminator.kind {
+            TerminatorKind::Drop { place, .. } => {
+                let ty = place.ty(body, self.tcx).ty;
+                (!self.is_component(ty))
+                    .then_some(errors::FinalizerOptionalViolation::Drop { span, ty })
+            }
+            TerminatorKind::Call { func, args, destination, .. } => {
+                if !self.is_owned_place(body, *destination, &mut FxHashSet::default()) {
+                    return Some(errors::FinalizerOptionalViolation::Write { span });
+                }
+                let ty::FnDef(callee, fn_args) = *func.ty(body, self.tcx).kind() else {
+                    return Some(errors::FinalizerOptionalViolation::IndirectCall { span });
+                };
+                let tcx = self.tcx;
+                if tcx.lang_items().drop_in_place_fn() == Some(callee)
+                    || tcx.is_diagnostic_item(sym::mem_drop, callee)
+                {
+                    // Dropping a value is fine, as long as it is one which
+                    // the type is allowed to drop.
+                    let ty = fn_args.type_at(0);
+                    (!self.is_component(ty))
+                        .then_some(errors::FinalizerOptionalViolation::Drop { span, ty })
+                } else if tcx.is_diagnostic_item(sym::dealloc, callee)
+                    || tcx.is_diagnostic_item(sym::allocator_deallocate, callee)
+                    || tcx.is_diagnostic_item(sym::mem_forget, callee)
+                    || tcx.is_diagnostic_item(sym::ptr_slice_from_raw_parts, callee)
+                    || tcx.is_diagnostic_item(sym::ptr_slice_from_raw_parts_mut, callee)
+                {
+                    None
+                } else if tcx.is_const_fn_raw(callee)
+                    && args.iter().all(|arg| self.is_owned_operand(body, &arg.node))
+                {
+                    // Const functions can't do anything but compute values
+                    // and read or write memory, so they are fine as long as
+                    // they can only write to the object being dropped.
+                    None
+                } else {
+                    Some(errors::FinalizerOptionalViolation::Call {
+                        span,
+                        callee: tcx.def_path_str(callee),
+                    })
+                }
+            }
+            TerminatorKind::InlineAsm { .. } => {
+                Some(errors::FinalizerOptionalViolation::InlineAsm { span })
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `place` is a local or part of the object being dropped, rather
+    /// than memory reached through some other pointer. `seen` holds the locals
+    /// whose definitions have already been followed.
+    fn is_owned_place(
+        &self,
+        body: &Body<'tcx>,
+        place: Place<'tcx>,
+        seen: &mut FxHashSet<Local>,
+    ) -> bool {
+        place.iter_projections().all(|(base, elem)| {
+            elem != ProjectionElem::Deref
+                || base.ty(body, self.tcx).ty.is_box()
+                || base.projection.is_empty() && self.points_to_owned(body, base.local, seen)
+        })
+    }
+
+    /// Whether `local` is `self`, or a pointer to a place which is owned as
+    /// described by `is_owned_place`.
+    fn points_to_owned(
+        &self,
+        body: &Body<'tcx>,
+        local: Local,
+        seen: &mut FxHashSet<Local>,
+    ) -> bool {
+        if local == Local::new(1) {
+            return true;
+        }
+        if !seen.insert(local) {
+            return false;
+        }
+        let mut defs = body
+            .basic_blocks
+            .iter()
+            .flat_map(|block| &block.statements)
+            .filter_map(|statement| statement.kind.as_assign())
+            .filter(|(place, _)| place.as_local() == Some(local))
+            .map(|(_, rvalue)| rvalue);
+        match (defs.next(), defs.next()) {
+            (Some(Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place)), None) => {
+                self.is_owned_place(body, *place, seen)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a call which is passed `operand` can't use it to write to
+    /// anything but the object being dropped.
+    fn is_owned_operand(&self, body: &Body<'tcx>, operand: &Operand<'tcx>) -> bool {
+        let ty = operand.ty(body, self.tcx);
+        if !self.holds_pointer(ty, &mut FxHashSet::default()) {
+            return true;
+        }
+        // A pointer to part of the object may be written through, as long as
+        // nothing else can be reached from it.
+        let (ty::Ref(_, pointee, _) | ty::RawPtr(pointee, _)) = *ty.kind() else {
+            return false;
+        };
+        if self.holds_pointer(pointee, &mut FxHashSet::default()) {
+            return false;
+        }
+        match operand.place().and_then(|place| place.as_local()) {
+            Some(local) => self.points_to_owned(body, local, &mut FxHashSet::default()),
+            None => false,
+        }
+    }
+
+    /// Whether a value of type `ty` may hold something which can be written
+    /// through. This looks through the fields of ADTs, since e.g. a
+    /// `NonNull<T>` is just a `*const T` which can be cast to a `*mut T`. The
+    /// values of type parameters can't be looked into by the callee, so these
+    /// are fine. `seen` holds the types which have already been looked at.
+    fn holds_pointer(&self, ty: Ty<'tcx>, seen: &mut FxHashSet<Ty<'tcx>>) -> bool {
+        let ty = self.normalize(ty);
+        if !seen.insert(ty) {
+            return false;
+        }
+        match *ty.kind() {
+            ty::Bool
+            | ty::Char
+            | ty::Int(_)
+            | ty::Uint(_)
+            | ty::Float(_)
+            | ty::Str
+            | ty::Never
+            | ty::FnDef(..)
+            | ty::FnPtr(_)
+            | ty::Param(_) => false,
+            ty::Ref(_, pointee, mutbl) => mutbl.is_mut() || self.holds_pointer(pointee, seen),
+            ty::Array(elem, _) | ty::Slice(elem) => self.holds_pointer(elem, seen),
+            ty::Tuple(tys) => tys.iter().any(|ty| self.holds_pointer(ty, seen)),
+            ty::Adt(def, _) if def.is_unsafe_cell() => true,
+            ty::Adt(def, args) => {
+                def.all_fields().any(|field| self.holds_pointer(field.ty(self.tcx, args), seen))
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether a value of type `ty` can be dropped without changing whether
+    /// the `#[finalizer_optional]` type needs a finalizer.
+    fn is_component(&self, ty: Ty<'tcx>) -> bool {
+        match *ty.kind() {
+            ty::Array(elem, _) | ty::Slice(elem) => self.is_component(elem),
+            _ => {
+                let ty = self.normalize(ty);
+                self.components.contains(&ty) || !ty.needs_drop(self.tcx, self.param_env)
+            }
+        }
+    }
+
+    fn normalize(&self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        self.tcx
+            .try_normalize_erasing_regions(self.param_env, ty)
+            .unwrap_or_else(|_| self.tcx.erase_regions(ty))
+    }
+}
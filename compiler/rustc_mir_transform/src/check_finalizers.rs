@@ -1,83 +1,1050 @@
-#![allow(rustc::untranslatable_diagnostic)]
-#![allow(rustc::diagnostic_outside_of_impl)]
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::LintDiagnostic;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId, LOCAL_CRATE};
 use rustc_hir::lang_items::LangItem;
-use rustc_middle::mir::visit::PlaceContext;
-use rustc_middle::mir::visit::Visitor;
+use rustc_hir::{HirId, CRATE_HIR_ID};
+use rustc_index::bit_set::BitSet;
+use rustc_index::IndexVec;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
 use rustc_middle::mir::*;
-use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_middle::query::TyCtxtAt;
+use rustc_middle::ty::adjustment::PointerCoercion;
+use rustc_middle::ty::{
+    self, GenericArgsRef, ParamEnv, Ty, TyCtxt, TypeFoldable, TypeVisitableExt,
+};
+use rustc_middle::util::Providers;
+use rustc_mir_dataflow::impls::borrowed_locals;
+use rustc_mir_dataflow::{Analysis, AnalysisDomain, ResultsCursor};
+use rustc_session::code_stats::GcFinalizerInfo;
+use rustc_session::lint::builtin::{GC_DANGLING_REFERENCE, UNSOUND_FINALIZER};
+use rustc_session::lint::Level;
 use rustc_span::symbol::sym;
-use rustc_span::Span;
+use rustc_span::{BytePos, FileNameDisplayPreference, Span};
 use rustc_trait_selection::infer::InferCtxtExt as _;
 use rustc_trait_selection::infer::TyCtxtInferExt;
 
+use crate::errors;
+use crate::remove_gc_drops::elides_gc_drop;
+
 #[derive(PartialEq)]
 pub struct CheckFinalizers;
 
 impl<'tcx> MirPass<'tcx> for CheckFinalizers {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
-        let ctor = tcx.get_diagnostic_item(sym::gc_ctor);
+        if tcx.get_diagnostic_item(sym::gc).is_none() {
+            return;
+        }
+
+        let def_id = body.source.def_id();
+        let param_env = tcx.param_env(def_id);
+        // A generic constructor which delegates to another (e.g. `From<T> for
+        // Gc<T>` calling `Gc::new`) is checked at its own call sites, so there
+        // is no need to check it again once it is instantiated.
+        let in_ctor = tcx.has_attr(def_id, sym::rustc_gc_constructor);
+        let mut generic_calls = Vec::new();
+
+        for call in find_gc_constructor_calls(tcx, param_env, body) {
+            // Which values a coroutine holds across suspension points (and so
+            // which auto traits it implements) is only known once its MIR has
+            // been borrow checked. This can't happen while we are still
+            // building the MIR of the body which creates it, so only the parts
+            // of the value which don't depend on that are checked here. The
+            // rest is checked by `CheckCoroutineFinalizers`.
+            let has_coroutine = has_coroutine(call.value_ty);
+            if has_coroutine {
+                check_gc_constructor_call_parts(tcx, param_env, &call, CoroutineParts::Eager);
+            } else {
+                check_gc_constructor_call(tcx, param_env, &call, None);
+            }
+
+            // The check above could only use the bounds in scope for any
+            // generic parameters in `value_ty`, so the collector must check it
+            // again for each concrete instantiation.
+            let is_generic = call.value_ty.has_non_region_param() && !in_ctor;
+            if (has_coroutine || is_generic) && call.kind != GcConstructorKind::Unfinalized {
+                fulfill_deferred_expectations(tcx, &call);
+            }
+            if is_generic {
+                generic_calls.push(call);
+            }
+        }
 
-        if ctor.is_none() {
+        body.gc_constructor_calls = generic_calls;
+    }
+}
+
+/// Checks the parts of the values moved into a `Gc` which depend on the
+/// witnesses of the coroutines they contain, i.e. the values those coroutines
+/// hold across suspension points. These are only known once the body has been
+/// borrow checked, which is why `CheckFinalizers` skips them.
+///
+/// Generic calls are instead checked in full by the collector. This pass only
+/// runs on MIR which is going to be codegened, so a check-only build (e.g.
+/// `cargo check`) does not report a coroutine which is unsafe to finalize
+/// because of a value it holds across a suspension point.
+#[derive(PartialEq)]
+pub struct CheckCoroutineFinalizers;
+
+impl<'tcx> MirPass<'tcx> for CheckCoroutineFinalizers {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.get_diagnostic_item(sym::gc).is_none() {
             return;
         }
 
-        let ctor_did = ctor.unwrap();
         let param_env = tcx.param_env(body.source.def_id());
+        for call in find_gc_constructor_calls(tcx, param_env, body) {
+            if has_coroutine(call.value_ty) && !call.value_ty.has_non_region_param() {
+                check_gc_constructor_call_parts(tcx, param_env, &call, CoroutineParts::Witnesses);
+            }
+        }
+    }
+}
 
-        for block in body.basic_blocks.iter() {
-            match &block.terminator {
-                Some(Terminator { kind: TerminatorKind::Call { func, args, .. }, source_info }) => {
-                    let func_ty = func.ty(body, tcx);
-                    if let ty::FnDef(fn_did, ..) = func_ty.kind() {
-                        if *fn_did == ctor_did {
-                            let arg = match &args[0].node {
-                                Operand::Copy(place) | Operand::Move(place) => {
-                                    body.local_decls()[place.local].source_info.span
-                                }
-                                Operand::Constant(con) => con.span,
-                            };
-                            let arg_ty = args[0].node.ty(body, tcx);
-
-                            let mut finalizer_cx =
-                                FinalizationCtxt { ctor: source_info.span, arg, tcx, param_env };
-                            finalizer_cx.check_for_dangling_refs(arg_ty);
-                            finalizer_cx.check(arg_ty);
-                        }
+/// Lint expectations are checked before monomorphization, so they can't see
+/// errors which are only found once part of `call` is checked later on (by the
+/// collector or by `CheckCoroutineFinalizers`). Instead, an `#[expect]` of
+/// either lint which applies to such a call is fulfilled here, whether or not
+/// the later check finds anything.
+fn fulfill_deferred_expectations<'tcx>(tcx: TyCtxt<'tcx>, call: &GcConstructorCall<'tcx>) {
+    let ClearCrossCrate::Set(lint_root) = call.lint_root else {
+        return;
+    };
+    for lint in [UNSOUND_FINALIZER, GC_DANGLING_REFERENCE] {
+        if let (Level::Expect(_), _) = tcx.lint_level_at_node(lint, lint_root) {
+            tcx.emit_node_span_lint(lint, lint_root, call.arg, errors::GcCheckDeferred);
+        }
+    }
+}
+
+/// Finds the calls to Gc constructors in `body` which need to be checked.
+fn find_gc_constructor_calls<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+) -> Vec<GcConstructorCall<'tcx>> {
+    let mut calls = Vec::new();
+    let mut unsize_sources = None;
+    for block in body.basic_blocks.iter() {
+        let Some(Terminator {
+            kind: TerminatorKind::Call { func, args, destination, .. },
+            source_info,
+        }) = &block.terminator
+        else {
+            continue;
+        };
+        let func_ty = func.ty(body, tcx);
+        let ty::FnDef(fn_did, fn_args) = func_ty.kind() else {
+            continue;
+        };
+        // Calls to constructors which never finalize the value are kept, so
+        // that `-Z print-gc-finalizers` can list them, but are not checked.
+        let Some(kind) = ctor_kind(tcx, param_env, *fn_did, fn_args) else {
+            continue;
+        };
+        // The value being moved into the `Gc` is found from the return type
+        // rather than the arguments, because not every constructor takes the
+        // value directly (e.g. `Default::default()`, or `From<Vec<T>> for
+        // Gc<[T]>`).
+        let ret_ty = destination.ty(body, tcx).ty;
+        let Some(value_ty) = gc_value_ty(tcx, ret_ty) else {
+            continue;
+        };
+        let arg = match args.first().map(|a| &a.node) {
+            Some(Operand::Copy(place) | Operand::Move(place)) => {
+                body.local_decls()[place.local].source_info.span
+            }
+            Some(Operand::Constant(con)) => con.span,
+            None => source_info.span,
+        };
+        let unsized_sources = match args.first().map(|a| &a.node) {
+            Some(op) if has_dyn(value_ty) && kind != GcConstructorKind::Unfinalized => {
+                unsize_sources.get_or_insert_with(|| UnsizeSources::new(tcx, body)).find(op)
+            }
+            _ => Vec::new(),
+        };
+        calls.push(GcConstructorCall {
+            value_ty,
+            unsized_sources,
+            kind,
+            ctor: source_info.span,
+            arg,
+            lint_root: match source_info.scope.lint_root(&body.source_scopes) {
+                Some(lint_root) => ClearCrossCrate::Set(lint_root),
+                None => ClearCrossCrate::Clear,
+            },
+        });
+    }
+    calls
+}
+
+pub(crate) fn provide(providers: &mut Providers) {
+    providers.hooks.check_mono_gc_constructor_call = check_mono_gc_constructor_call;
+    providers.finalizer_safety = finalizer_safety;
+    providers.finalizer_uses = finalizer_uses;
+}
+
+/// Checks a Gc constructor call from a generic body, now that the type of the
+/// value it moves into the `Gc` is known.
+fn check_mono_gc_constructor_call<'tcx>(tcx: TyCtxtAt<'tcx>, call: GcConstructorCall<'tcx>) {
+    if call.value_ty.has_non_region_param() {
+        // Polymorphization can leave unused parameters in place. There is
+        // nothing more to learn about those than the generic check already did.
+        return;
+    }
+    check_gc_constructor_call(tcx.tcx, ParamEnv::reveal_all(), &call, Some(tcx.span));
+}
+
+/// Returns how the callee finalizes its value if it is a Gc constructor, or
+/// `None` otherwise.
+///
+/// Calls to trait methods (e.g. `<Gc<T> as Default>::default`) are resolved to
+/// the impl where possible, since that is where the attribute lives.
+fn ctor_kind<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    fn_did: DefId,
+    fn_args: GenericArgsRef<'tcx>,
+) -> Option<GcConstructorKind> {
+    let callee = if tcx.trait_of_item(fn_did).is_some() {
+        match ty::Instance::resolve(tcx, param_env, fn_did, fn_args) {
+            Ok(Some(instance)) => instance.def_id(),
+            _ => fn_did,
+        }
+    } else {
+        fn_did
+    };
+    let attr = tcx.get_attr(callee, sym::rustc_gc_constructor)?;
+    let Some(list) = attr.meta_item_list() else {
+        return Some(GcConstructorKind::Checked);
+    };
+    match list.first().map(|m| m.name_or_empty()) {
+        Some(sym::local) => Some(GcConstructorKind::Local),
+        Some(sym::unsynchronised) => Some(GcConstructorKind::Unsynchronised),
+        Some(sym::unfinalized) => Some(GcConstructorKind::Unfinalized),
+        _ => {
+            tcx.dcx().emit_err(errors::GcInvalidConstructorArg { span: attr.span });
+            None
+        }
+    }
+}
+
+fn has_dyn(ty: Ty<'_>) -> bool {
+    ty.walk().filter_map(|arg| arg.as_type()).any(|t| matches!(t.kind(), ty::Dynamic(..)))
+}
+
+/// Given an unsizing coercion from `src` to `dst`, finds the concrete type
+/// which becomes a trait object, and that trait object type.
+fn unsized_pair<'tcx>(src: Ty<'tcx>, dst: Ty<'tcx>) -> Option<(Ty<'tcx>, Ty<'tcx>)> {
+    match (src.kind(), dst.kind()) {
+        (_, ty::Dynamic(..)) => Some((src, dst)),
+        (ty::Adt(src_def, src_args), ty::Adt(dst_def, dst_args)) if src_def == dst_def => {
+            // A `CoerceUnsized` smart pointer (e.g. `Box<T>`, `Rc<T>`), where
+            // the generic argument which differs is the one being unsized.
+            let (src, dst) =
+                std::iter::zip(src_args.types(), dst_args.types()).find(|(s, d)| s != d)?;
+            unsized_pair(src, dst)
+        }
+        _ => unsized_pair(src.builtin_deref(true)?.ty, dst.builtin_deref(true)?.ty),
+    }
+}
+
+/// Where the single definition of a local in a body comes from, as far as
+/// `UnsizeSources` is concerned.
+#[derive(Clone, Copy)]
+enum LocalDef {
+    /// Not written to (yet).
+    None,
+    /// Written by exactly one plain assignment, at this location.
+    Assign(Location),
+    /// Written more than once, partially, through a call, or borrowed (so it
+    /// could be written to through a reference).
+    Opaque,
+}
+
+/// Finds the concrete types which were unsized into the trait objects in a
+/// value, by following its definition back through the body which constructs
+/// it. This means that in `Gc::new(Foo(Box::new(x) as Box<dyn Trait>))`, the
+/// trait object can be checked as if it were a `Box<X>`.
+///
+/// This only looks within that one body, so a trait object which arrives as a
+/// function argument (or is returned by a call) is still treated as unknown,
+/// even if every caller passes in a type which is safe to finalize.
+struct UnsizeSources<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    defs: IndexVec<Local, LocalDef>,
+}
+
+impl<'a, 'tcx> UnsizeSources<'a, 'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, body: &'a Body<'tcx>) -> Self {
+        let defs = IndexVec::from_elem(LocalDef::None, &body.local_decls);
+        let mut sources = UnsizeSources { tcx, body, defs };
+        sources.visit_body(body);
+        sources
+    }
+
+    /// Returns the concrete types unsized into each trait object type in
+    /// `op`, as `(dyn_ty, concrete_ty)` pairs. If any trait object came from
+    /// somewhere which we can't follow (e.g. a function argument), no sources
+    /// are returned at all, since the trait object's concrete type could be
+    /// anything.
+    fn find(&self, op: &Operand<'tcx>) -> Vec<(Ty<'tcx>, Ty<'tcx>)> {
+        let mut sources = Vec::new();
+        let mut visited = FxHashSet::default();
+        if self.find_in_operand(op, &mut sources, &mut visited) { sources } else { Vec::new() }
+    }
+
+    fn find_in_operand(
+        &self,
+        op: &Operand<'tcx>,
+        sources: &mut Vec<(Ty<'tcx>, Ty<'tcx>)>,
+        visited: &mut FxHashSet<Local>,
+    ) -> bool {
+        if !has_dyn(op.ty(self.body, self.tcx)) {
+            return true;
+        }
+        let Some(local) = op.place().and_then(|place| place.as_local()) else {
+            return false;
+        };
+        if !visited.insert(local) {
+            return false;
+        }
+        let LocalDef::Assign(location) = self.defs[local] else {
+            return false;
+        };
+        let stmt = &self.body.basic_blocks[location.block].statements[location.statement_index];
+        let StatementKind::Assign(box (_, rvalue)) = &stmt.kind else {
+            return false;
+        };
+        match rvalue {
+            Rvalue::Use(op) => self.find_in_operand(op, sources, visited),
+            Rvalue::Cast(CastKind::PointerCoercion(PointerCoercion::Unsize), op, dst) => {
+                let Some((src, dyn_ty)) = unsized_pair(op.ty(self.body, self.tcx), *dst) else {
+                    return false;
+                };
+                sources.push((self.tcx.erase_regions(dyn_ty), src));
+                // The concrete type may contain trait objects of its own.
+                self.find_in_operand(op, sources, visited)
+            }
+            Rvalue::Aggregate(_, ops) => {
+                ops.iter().all(|op| self.find_in_operand(op, sources, visited))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for UnsizeSources<'_, 'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        let def = &mut self.defs[place.local];
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store) if place.projection.is_empty() => {
+                *def = match def {
+                    LocalDef::None => LocalDef::Assign(location),
+                    _ => LocalDef::Opaque,
+                };
+            }
+            // Moving, copying or dropping a value doesn't change what it is.
+            PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::Copy
+                | NonMutatingUseContext::Move
+                | NonMutatingUseContext::Inspect
+                | NonMutatingUseContext::PlaceMention,
+            )
+            | PlaceContext::MutatingUse(MutatingUseContext::Drop)
+            | PlaceContext::NonUse(_) => {}
+            _ => *def = LocalDef::Opaque,
+        }
+        self.super_place(place, context, location);
+    }
+}
+
+fn has_coroutine(ty: Ty<'_>) -> bool {
+    ty.walk().filter_map(|arg| arg.as_type()).any(|t| matches!(t.kind(), ty::Coroutine(..)))
+}
+
+/// Finds the `T` in the first `Gc<T>` which appears in `ty`.
+fn gc_value_ty<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    ty.walk().filter_map(|arg| arg.as_type()).find(|t| t.is_gc(tcx)).map(|gc| match gc.kind() {
+        ty::Adt(_, args) => args.type_at(0),
+        _ => unreachable!(),
+    })
+}
+
+/// Checks a call to a Gc constructor, reporting any reason why the value it
+/// moves into the `Gc` can't be safely finalized.
+fn check_gc_constructor_call<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    call: &GcConstructorCall<'tcx>,
+    instantiated_at: Option<Span>,
+) {
+    if tcx.sess.opts.unstable_opts.print_gc_finalizers && !call.value_ty.has_non_region_param() {
+        record_gc_finalizer(tcx, param_env, call);
+    }
+    if call.kind == GcConstructorKind::Unfinalized {
+        return;
+    }
+
+    let cx = GcConstructorCallCtxt { tcx, param_env, call, instantiated_at };
+    cx.check_for_dangling_refs(call.value_ty);
+
+    // If the value implements `Finalize`, its `finalize` method is called by
+    // the finalizer instead of its drop glue, so that is the only body which
+    // needs to be checked. `Drop` impls on the type and its fields never run
+    // in a finalizer, so they are left unrestricted.
+    if call.value_ty.is_finalize(tcx, param_env) {
+        let mut finalizer_cx = FinalizationCtxt::new(tcx, param_env, call.kind);
+        finalizer_cx.check_finalize(call.value_ty);
+        cx.report(&finalizer_cx.errors, &mut FxHashSet::default());
+    } else {
+        cx.check_drop(call.value_ty, &mut FxHashSet::default());
+    }
+}
+
+/// Which parts of a value containing a coroutine to check.
+#[derive(Clone, Copy, PartialEq)]
+enum CoroutineParts {
+    /// The parts which can be checked before the coroutine has been borrow
+    /// checked: its upvars, and any other component of the value which does
+    /// not contain a coroutine.
+    Eager,
+    /// The values which the coroutine holds across suspension points, and any
+    /// component of the value whose drop glue can't be split up (e.g. a `Box`
+    /// of a coroutine).
+    Witnesses,
+}
+
+/// Checks one part of a call to a Gc constructor whose value contains a
+/// coroutine.
+fn check_gc_constructor_call_parts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    call: &GcConstructorCall<'tcx>,
+    parts: CoroutineParts,
+) {
+    if parts == CoroutineParts::Witnesses && tcx.sess.opts.unstable_opts.print_gc_finalizers {
+        record_gc_finalizer(tcx, ParamEnv::reveal_all(), call);
+    }
+    if call.kind == GcConstructorKind::Unfinalized {
+        return;
+    }
+
+    let mut eager = Vec::new();
+    let mut deferred = Vec::new();
+    split_coroutine_parts(tcx, call.value_ty, &mut eager, &mut deferred);
+    let (param_env, tys) = match parts {
+        CoroutineParts::Eager => (param_env, eager),
+        // Witnesses are only checked for concrete calls, so the opaque types
+        // in them (e.g. of awaited futures) can be revealed, as the collector
+        // would.
+        CoroutineParts::Witnesses => {
+            let param_env = ParamEnv::reveal_all();
+            let tys = deferred
+                .into_iter()
+                .flat_map(|ty| match *ty.kind() {
+                    ty::CoroutineWitness(def_id, args) => tcx
+                        .mir_coroutine_witnesses(def_id)
+                        .iter()
+                        .flat_map(|witness| &witness.field_tys)
+                        .map(|field_ty| ty::EarlyBinder::bind(field_ty.ty).instantiate(tcx, args))
+                        .collect(),
+                    _ => vec![ty],
+                })
+                .map(|ty| tcx.try_normalize_erasing_regions(param_env, ty).unwrap_or(ty))
+                .collect();
+            (param_env, tys)
+        }
+    };
+
+    let cx = GcConstructorCallCtxt { tcx, param_env, call, instantiated_at: None };
+    let mut seen = FxHashSet::default();
+    for ty in tys {
+        cx.check_for_dangling_refs(ty);
+        cx.check_drop(ty, &mut seen);
+    }
+}
+
+/// Splits `ty` into the components which can be checked before the
+/// coroutines in it have been borrow checked (`eager`), and those which can't
+/// (`deferred`). The latter are the witnesses of the coroutines, and any type
+/// containing a coroutine whose drop glue does more than drop its components.
+fn split_coroutine_parts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    eager: &mut Vec<Ty<'tcx>>,
+    deferred: &mut Vec<Ty<'tcx>>,
+) {
+    if !has_coroutine(ty) {
+        eager.push(ty);
+        return;
+    }
+    let mut split = |ty| split_coroutine_parts(tcx, ty, eager, deferred);
+    match *ty.kind() {
+        ty::Coroutine(_, args) => {
+            let args = args.as_coroutine();
+            args.upvar_tys().iter().for_each(&mut split);
+            deferred.push(args.witness());
+        }
+        ty::Closure(_, args) => args.as_closure().upvar_tys().iter().for_each(split),
+        ty::CoroutineClosure(_, args) => {
+            args.as_coroutine_closure().upvar_tys().iter().for_each(split)
+        }
+        ty::Tuple(tys) => tys.iter().for_each(split),
+        ty::Array(ty, _) | ty::Slice(ty) => split(ty),
+        ty::Adt(def, args) if !def.has_dtor(tcx) && !has_finalize_impl(tcx, def) => {
+            def.all_fields().for_each(|field| split(field.ty(tcx, args)))
+        }
+        _ => deferred.push(ty),
+    }
+}
+
+/// Whether `def` implements `Finalize`. Unlike `Ty::is_finalize`, this doesn't
+/// use trait selection, which may need the witness of a coroutine in the
+/// type's arguments.
+fn has_finalize_impl<'tcx>(tcx: TyCtxt<'tcx>, def: ty::AdtDef<'tcx>) -> bool {
+    tcx.lang_items().finalize_trait().is_some_and(|finalize| {
+        tcx.all_impls(finalize)
+            .any(|impl_did| tcx.type_of(impl_did).instantiate_identity().ty_adt_def() == Some(def))
+    })
+}
+
+/// Records whether a concrete Gc constructor call registers a finalizer, and
+/// why, for `-Z print-gc-finalizers`.
+fn record_gc_finalizer<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    call: &GcConstructorCall<'tcx>,
+) {
+    let ty = call.value_ty;
+    let registered =
+        call.kind != GcConstructorKind::Unfinalized && ty.needs_finalizer(tcx, param_env);
+    let (file, lo_line, lo_col, hi_line, hi_col) =
+        tcx.sess.source_map().span_to_location_info(call.ctor);
+    let file = match file {
+        Some(file) => file.name.display(FileNameDisplayPreference::Remapped).to_string(),
+        None => "no-location".to_string(),
+    };
+    tcx.sess.code_stats.record_gc_finalizer(GcFinalizerInfo {
+        file,
+        lo: (lo_line, lo_col),
+        hi: (hi_line, hi_col),
+        type_description: ty.to_string(),
+        registered,
+        drop_elided: elides_gc_drop(tcx, param_env, ty, LOCAL_CRATE),
+        reasons: if registered { ty.finalizer_reasons(tcx, param_env) } else { Vec::new() },
+    });
+}
+
+/// A reference found by the dangling reference check.
+struct DanglingRef {
+    /// The field which holds the reference, if it is held by one.
+    field: Option<DefId>,
+}
+
+struct GcConstructorCallCtxt<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    call: &'a GcConstructorCall<'tcx>,
+    /// Where a generic body containing the constructor call was instantiated,
+    /// if this is a check of a monomorphized call.
+    instantiated_at: Option<Span>,
+}
+
+impl<'a, 'tcx> GcConstructorCallCtxt<'a, 'tcx> {
+    fn check_for_dangling_refs(&self, ty: Ty<'tcx>) {
+        if !self.tcx.needs_finalizer_raw(self.param_env.and(ty)) {
+            return;
+        }
+        if let Some(dangling) = self.find_dangling_ref(ty, &mut FxHashSet::default()) {
+            let (instantiated_ctor, instantiated_at) = self.instantiation();
+            self.tcx.emit_node_span_lint(
+                GC_DANGLING_REFERENCE,
+                self.lint_root(),
+                self.call.arg,
+                errors::GcDanglingReference {
+                    arg: self.call.arg,
+                    arg_desc: self.arg_snippet(),
+                    ctor: self.call.ctor,
+                    instantiated_ctor,
+                    instantiated_at,
+                    field: dangling.field.map(|did| errors::GcDanglingField {
+                        span: self.tcx.def_span(did),
+                        field: self.tcx.item_name(did),
+                        adt: self.tcx.def_path_str(self.tcx.parent(did)),
+                    }),
+                    new_unfinalizable: self.suggest_new_unfinalizable(),
+                },
+            );
+        }
+    }
+
+    /// Searches `ty` for a reference which may no longer be valid when the
+    /// value is finalized.
+    ///
+    /// Regions are erased from the types in MIR, so the only references we
+    /// know to be `'static` are those written as such in the type of a field
+    /// (e.g. `name: &'static str`), which we find by walking through the
+    /// fields of ADTs. Types which we can't look into, such as type parameters
+    /// and trait objects, must be `ReferenceFree`.
+    fn find_dangling_ref(
+        &self,
+        ty: Ty<'tcx>,
+        seen: &mut FxHashSet<Ty<'tcx>>,
+    ) -> Option<DanglingRef> {
+        if !seen.insert(ty) || self.is_reference_free(ty) {
+            return None;
+        }
+        match *ty.kind() {
+            ty::Ref(region, ..) => (!region.is_static()).then_some(DanglingRef { field: None }),
+            ty::RawPtr(inner, _) | ty::Slice(inner) | ty::Array(inner, _) => {
+                self.find_dangling_ref(inner, seen)
+            }
+            ty::Tuple(tys) => tys.iter().find_map(|ty| self.find_dangling_ref(ty, seen)),
+            ty::Adt(def, args) => def.all_fields().find_map(|field| {
+                let field_ty = self.tcx.type_of(field.did).instantiate(self.tcx, args);
+                let mut dangling = self.find_dangling_ref(field_ty, seen)?;
+                // Report the innermost field, which is where the reference is
+                // actually written.
+                dangling.field.get_or_insert(field.did);
+                Some(dangling)
+            }),
+            ty::Closure(_, args) => {
+                args.as_closure().upvar_tys().iter().find_map(|ty| self.find_dangling_ref(ty, seen))
+            }
+            _ => Some(DanglingRef { field: None }),
+        }
+    }
+
+    fn is_reference_free(&self, ty: Ty<'tcx>) -> bool {
+        let reference_free = self.tcx.get_diagnostic_item(sym::ReferenceFree).unwrap();
+        self.tcx
+            .infer_ctxt()
+            .build()
+            .type_implements_trait(reference_free, [ty], self.param_env)
+            .must_apply_modulo_regions()
+    }
+
+    /// Checks the drop glue of `ty`, which is either the value moved into the
+    /// `Gc` or a type which was unsized into a trait object within it.
+    fn check_drop(&self, ty: Ty<'tcx>, seen: &mut FxHashSet<Ty<'tcx>>) {
+        let ty = self.tcx.erase_regions(ty);
+        if seen.insert(ty) {
+            let errors = self.tcx.finalizer_safety((self.call.kind, self.param_env.and(ty)));
+            self.report(errors, seen);
+        }
+    }
+
+    fn report(&self, errors: &[FinalizerError<'tcx>], seen: &mut FxHashSet<Ty<'tcx>>) {
+        for error in errors {
+            match *error {
+                FinalizerError::UnknownDrop(ty) => {
+                    // If we saw the coercions which created a trait object, we
+                    // can check the concrete types instead.
+                    let mut sources = self
+                        .call
+                        .unsized_sources
+                        .iter()
+                        .filter(|&&(dyn_ty, _)| dyn_ty == ty)
+                        .peekable();
+                    if sources.peek().is_none() {
+                        self.emit_unknown_drop();
                     }
+                    sources.for_each(|&(_, src)| self.check_drop(src, seen));
+                }
+                FinalizerError::UnsafeUse { ty, span, method, call_site } => {
+                    self.emit_unsafe_use(ty, span, method, call_site)
                 }
-                _ => {}
             }
         }
     }
+
+    /// A description of the value moved into the `Gc`, for diagnostics.
+    fn arg_snippet(&self) -> String {
+        match self.tcx.sess.source_map().span_to_snippet(self.call.arg) {
+            Ok(arg) => format!("`{arg}`"),
+            Err(_) => "this value".to_string(),
+        }
+    }
+
+    fn emit_unknown_drop(&self) {
+        let (instantiated_ctor, instantiated_at) = self.instantiation();
+        self.emit_lint(errors::GcUnknownDrop {
+            arg: self.call.arg,
+            arg_desc: self.arg_snippet(),
+            ctor: self.call.ctor,
+            instantiated_ctor,
+            instantiated_at,
+            new_unfinalizable: self.suggest_new_unfinalizable(),
+            finalize_unchecked: self.suggest_finalize_unchecked(),
+        });
+    }
+
+    fn emit_unsafe_use(
+        &self,
+        ty: Ty<'tcx>,
+        span: Span,
+        method: &'static str,
+        call_site: Option<Span>,
+    ) {
+        let (instantiated_ctor, instantiated_at) = self.instantiation();
+        self.emit_lint(errors::GcUnsafeUse {
+            arg: self.call.arg,
+            arg_desc: self.arg_snippet(),
+            use_span: span,
+            use_reason: span,
+            call_site,
+            method,
+            is_gc: ty.is_gc(self.tcx),
+            instantiated_ctor,
+            instantiated_at,
+            new_unfinalizable: self.suggest_new_unfinalizable(),
+            finalize_unchecked: self.suggest_finalize_unchecked(),
+            finalizer_safe: self.suggest_finalizer_safe(ty),
+        });
+    }
+
+    fn emit_lint(&self, decorator: impl for<'b> LintDiagnostic<'b, ()>) {
+        self.tcx.emit_node_span_lint(UNSOUND_FINALIZER, self.lint_root(), self.call.arg, decorator);
+    }
+
+    /// Calls in MIR from other crates have no lint levels of their own, so
+    /// they use the levels set for the crate being compiled.
+    fn lint_root(&self) -> HirId {
+        match self.call.lint_root {
+            ClearCrossCrate::Set(lint_root) => lint_root,
+            ClearCrossCrate::Clear => CRATE_HIR_ID,
+        }
+    }
+
+    /// The constructor call and the place it was instantiated from, if this is
+    /// a check of a monomorphized call.
+    fn instantiation(&self) -> (Option<Span>, Option<Span>) {
+        // Roots of the collector, such as `main`, are not instantiated from
+        // anywhere in particular.
+        match self.instantiated_at {
+            Some(span) if !span.is_dummy() => (Some(self.call.ctor), Some(span)),
+            _ => (None, None),
+        }
+    }
+
+    /// Returns whether the constructor call is written as `Gc::new(..)` with
+    /// the value as its argument, in which case we can suggest rewriting it.
+    fn is_plain_gc_new(&self) -> bool {
+        let ctor = self.call.ctor;
+        !ctor.from_expansion()
+            && ctor != self.call.arg
+            && ctor.contains(self.call.arg)
+            && self
+                .tcx
+                .sess
+                .source_map()
+                .span_to_snippet(ctor)
+                .is_ok_and(|snippet| snippet.starts_with("Gc::new("))
+    }
+
+    fn suggest_new_unfinalizable(&self) -> Option<errors::GcNewUnfinalizable> {
+        if !self.is_plain_gc_new() {
+            return None;
+        }
+        let path_end = self.call.ctor.lo() + BytePos("Gc::new".len() as u32);
+        Some(errors::GcNewUnfinalizable { span: self.call.ctor.with_lo(path_end).shrink_to_lo() })
+    }
+
+    fn suggest_finalize_unchecked(&self) -> Option<errors::GcFinalizeUnchecked> {
+        if !self.is_plain_gc_new() || self.call.kind != GcConstructorKind::Checked {
+            return None;
+        }
+        Some(errors::GcFinalizeUnchecked {
+            lo: self.call.arg.shrink_to_lo(),
+            hi: self.call.arg.shrink_to_hi(),
+        })
+    }
+
+    /// If `ty` is a local, non-generic type which is only missing a
+    /// `FinalizerSafe` impl, suggests adding one after its definition.
+    fn suggest_finalizer_safe(&self, ty: Ty<'tcx>) -> Option<errors::GcFinalizerSafe> {
+        let ty::Adt(def, args) = ty.kind() else { return None };
+        let did = def.did().as_local()?;
+        if !args.is_empty() || ty.is_gc(self.tcx) {
+            return None;
+        }
+        let finalizer_cx = FinalizationCtxt::new(self.tcx, self.param_env, self.call.kind);
+        if !finalizer_cx.is_send(ty) || !finalizer_cx.is_sync(ty) {
+            return None;
+        }
+        let span = self.tcx.hir().span(self.tcx.local_def_id_to_hir_id(did));
+        Some(errors::GcFinalizerSafe {
+            span: span.shrink_to_hi(),
+            ty: self.tcx.item_name(def.did()).to_string(),
+        })
+    }
+}
+
+fn finalizer_safety<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    (kind, key): (GcConstructorKind, ty::ParamEnvAnd<'tcx, Ty<'tcx>>),
+) -> &'tcx [FinalizerError<'tcx>] {
+    let mut finalizer_cx = FinalizationCtxt::new(tcx, key.param_env, kind);
+    finalizer_cx.check(key.value);
+    tcx.arena.alloc_from_iter(finalizer_cx.errors)
+}
+
+fn finalizer_uses<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+) -> Option<&'tcx [FinalizerUse<'tcx>]> {
+    match tcx.def_kind(def_id) {
+        DefKind::Impl { .. } => {
+            // The impl may have other items (e.g. from specialization), so
+            // find the method by the trait item which it implements.
+            let trait_did = tcx.trait_id_of_impl(def_id.to_def_id())?;
+            let trait_method = *tcx.associated_item_def_ids(trait_did).first()?;
+            let method = *tcx.impl_item_implementor_ids(def_id).get(&trait_method)?;
+            tcx.finalizer_uses(method)
+        }
+        DefKind::Fn | DefKind::AssocFn | DefKind::Closure => {
+            if !tcx.is_mir_available(def_id) {
+                return None;
+            }
+            // This is summarized from the built MIR, since the optimized MIR
+            // of a function can depend on that of the function which is being
+            // checked (e.g. because of inlining), which would be a cycle.
+            // `mir_promoted` makes sure this is computed before it steals it.
+            let body = &*tcx.mir_built(def_id).borrow();
+            // The summary is shared by every call, so we don't know which
+            // arguments are derived from the object being finalized. All of
+            // them are assumed to be. For a `drop` or `finalize` method, that
+            // is just the receiver.
+            let mut roots = BitSet::new_empty(body.local_decls.len());
+            for arg in body.args_iter() {
+                roots.insert(arg);
+            }
+            Some(tcx.arena.alloc_from_iter(FinalizerUseCollector::collect(tcx, body, roots)))
+        }
+        _ => None,
+    }
+}
+
+/// Collects the uses of the object being finalized in the `drop` or
+/// `finalize` method whose MIR is `body`.
+fn collect_finalizer_uses<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Vec<FinalizerUse<'tcx>> {
+    // Only the receiver (`&mut self`) refers to the object being finalized
+    // on entry.
+    let mut roots = BitSet::new_empty(body.local_decls.len());
+    roots.insert(Local::new(1));
+    FinalizerUseCollector::collect(tcx, body, roots)
 }
 
 struct FinalizationCtxt<'tcx> {
-    ctor: Span,
-    arg: Span,
     tcx: TyCtxt<'tcx>,
     param_env: ParamEnv<'tcx>,
+    kind: GcConstructorKind,
+    errors: Vec<FinalizerError<'tcx>>,
 }
 
 impl<'tcx> FinalizationCtxt<'tcx> {
-    fn check_for_dangling_refs(&mut self, ty: Ty<'tcx>) {
-        if !self.is_reference_free(ty) && self.tcx.needs_finalizer_raw(self.param_env.and(ty)) {
-            let arg = self.tcx.sess.source_map().span_to_snippet(self.arg).unwrap();
-            let mut err = self
-                .tcx
-                .sess
-                .psess
-                .dcx
-                .struct_span_err(self.arg, format!("`{arg}` cannot be safely constructed.",));
-            err.span_label(
-                self.arg,
-                "contains a reference (&) which may no longer be valid when it is finalized.",
-            );
-            err.span_label(
-                self.ctor,
-                format!("`Gc::new` requires that a type is reference free.",),
-            );
-            err.emit();
+    fn new(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, kind: GcConstructorKind) -> Self {
+        FinalizationCtxt { tcx, param_env, kind, errors: Vec::new() }
+    }
+
+    /// Checks the `finalize` method of a value which implements `Finalize`.
+    fn check_finalize(&mut self, ty: Ty<'tcx>) {
+        if self.is_finalize_unchecked(ty) {
+            return;
+        }
+
+        if self.is_send(ty) && self.is_sync(ty) && self.is_finalizer_safe(ty) {
+            return;
         }
+
+        if let ty::Adt(_, substs) = ty.kind() {
+            let finalize_trait = self.tcx.require_lang_item(LangItem::Finalize, None);
+            self.check_method_body(finalize_trait, ty, substs, "fn finalize(&mut)");
+        }
+    }
+
+    /// Checks the uses of the object being finalized in the (only) method of
+    /// `trait_did` as implemented for `ty`.
+    fn check_method_body(
+        &mut self,
+        trait_did: DefId,
+        ty: Ty<'tcx>,
+        substs: GenericArgsRef<'tcx>,
+        method: &'static str,
+    ) {
+        let method_did = self.tcx.associated_item_def_ids(trait_did)[0];
+        let substs = self.tcx.mk_args_trait(ty, substs.into_iter());
+        let instance = match ty::Instance::resolve(self.tcx, self.param_env, method_did, substs) {
+            Ok(Some(instance @ ty::Instance { def: ty::InstanceDef::Item(_), .. })) => instance,
+            _ => {
+                // The impl could not be selected (e.g. because of errors
+                // elsewhere), so we don't know what the method does.
+                self.errors.push(FinalizerError::UnknownDrop(ty));
+                return;
+            }
+        };
+        // Prefer the summary of the impl, which is available even when the
+        // impl is upstream and its MIR was not encoded. Crates which don't use
+        // `Gc` don't record summaries, so fall back to the MIR for those. This
+        // is never needed for local impls, whose optimized MIR may not be
+        // built yet.
+        let impl_did = self.tcx.parent(instance.def_id());
+        let uses = match self.tcx.finalizer_uses(impl_did) {
+            Some(uses) => uses.to_vec(),
+            None if !impl_did.is_local() && self.tcx.is_mir_available(instance.def_id()) => {
+                collect_finalizer_uses(self.tcx, self.tcx.instance_mir(instance.def))
+            }
+            None => {
+                self.errors.push(FinalizerError::UnknownDrop(ty));
+                return;
+            }
+        };
+        self.check_uses(&uses, instance, method, None, &mut FxHashSet::default());
+    }
+
+    /// Checks the uses of the object being finalized in the body of
+    /// `instance`, which is either the finalizing method itself, or a function
+    /// which it (transitively) calls. `visited` holds every function (and the
+    /// arguments derived from the receiver on entry) which has been checked so
+    /// far for this method, so that each one is only checked once and
+    /// recursive calls terminate.
+    fn check_uses(
+        &mut self,
+        uses: &[FinalizerUse<'tcx>],
+        instance: ty::Instance<'tcx>,
+        method: &'static str,
+        call_site: Option<Span>,
+        visited: &mut FxHashSet<(ty::Instance<'tcx>, BitSet<Local>)>,
+    ) {
+        let same_call = |a: &FinalizerUse<'tcx>, b: &FinalizerUse<'tcx>| match (a, b) {
+            (
+                FinalizerUse::CallArg { func: a_func, span: a_span, .. },
+                FinalizerUse::CallArg { func: b_func, span: b_span, .. },
+            ) => a_func == b_func && a_span == b_span,
+            _ => false,
+        };
+        for group in uses.chunk_by(same_call) {
+            match group[0] {
+                FinalizerUse::Field { ty, span } => {
+                    let ty = self.monomorphize(instance, ty);
+                    if !self.is_safe(ty) {
+                        self.errors.push(FinalizerError::UnsafeUse { ty, span, method, call_site });
+                    }
+                }
+                FinalizerUse::CallArg { func, span, .. } => {
+                    // If a function is passed a value derived from the
+                    // receiver (e.g. `self.close()`), it may use
+                    // `!FinalizerSafe` fields through it, so we check its body
+                    // too. This recurses through any further calls which pass
+                    // the value on. Values whose types are safe to use in a
+                    // finalizer can't lead anywhere unsafe, so these calls are
+                    // not followed. If we can't find the callee's body (e.g. a
+                    // dynamic call, or an upstream function whose MIR was not
+                    // encoded), we must conservatively assume it is unsound.
+                    let mut roots = Vec::new();
+                    let mut unsafe_arg = None;
+                    for arg in group {
+                        let FinalizerUse::CallArg { index, ty, .. } = *arg else {
+                            continue;
+                        };
+                        roots.push(index as usize);
+                        let ty = self.monomorphize(instance, ty);
+                        if unsafe_arg.is_none() && !self.is_safe(ty) {
+                            unsafe_arg = Some(ty);
+                        }
+                    }
+                    if let Some(ty) = unsafe_arg {
+                        let func = self.monomorphize(instance, func);
+                        let callee_site = Some(call_site.unwrap_or(span));
+                        if !self.check_callee(func, &roots, method, callee_site, visited) {
+                            self.errors.push(FinalizerError::UnsafeUse {
+                                ty,
+                                span,
+                                method,
+                                call_site,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the body of a function which is passed a value derived from the
+    /// receiver. `roots` are the callee's arguments which are derived. Returns
+    /// `false` if the callee's body could not be found, in which case the call
+    /// must be assumed to be unsound.
+    fn check_callee(
+        &mut self,
+        func: Ty<'tcx>,
+        roots: &[usize],
+        method: &'static str,
+        call_site: Option<Span>,
+        visited: &mut FxHashSet<(ty::Instance<'tcx>, BitSet<Local>)>,
+    ) -> bool {
+        let tcx = self.tcx;
+        let ty::FnDef(def_id, args) = *func.kind() else {
+            return false;
+        };
+        let instance = match ty::Instance::resolve(tcx, self.param_env, def_id, args) {
+            Ok(Some(instance)) => instance,
+            _ => return false,
+        };
+        let ty::InstanceDef::Item(callee) = instance.def else {
+            return false;
+        };
+        if callee.is_local() || !tcx.is_mir_available(callee) {
+            // Upstream crates summarize the non-generic functions which their
+            // finalizers pass the object to, since the MIR of these is usually
+            // not encoded. Local functions are summarized from their built
+            // MIR, as their optimized MIR may depend on the body which is
+            // being checked (e.g. if it is the callee's caller).
+            let Some(uses) = tcx.finalizer_uses(callee) else {
+                return false;
+            };
+            if visited.insert((instance, BitSet::new_empty(0))) {
+                self.check_uses(uses, instance, method, call_site, visited);
+            }
+            return true;
+        }
+
+        let body = tcx.instance_mir(instance.def);
+        let mut callee_roots = BitSet::new_empty(body.local_decls.len());
+        for &i in roots {
+            if i < body.arg_count {
+                callee_roots.insert(Local::new(i + 1));
+            }
+        }
+        if !visited.insert((instance, callee_roots.clone())) {
+            return true;
+        }
+
+        let uses = FinalizerUseCollector::collect(tcx, body, callee_roots);
+        self.check_uses(&uses, instance, method, call_site, visited);
+        true
+    }
+
+    fn monomorphize<T: TypeFoldable<TyCtxt<'tcx>>>(
+        &self,
+        instance: ty::Instance<'tcx>,
+        value: T,
+    ) -> T {
+        // Normalization can fail in a generic context, in which case the
+        // unnormalized value is still good enough to check against.
+        instance
+            .try_instantiate_mir_and_normalize_erasing_regions(
+                self.tcx,
+                self.param_env,
+                ty::EarlyBinder::bind(value.clone()),
+            )
+            .unwrap_or(value)
+    }
+
+    /// Whether `ty` can be used in a finalizer.
+    fn is_safe(&self, ty: Ty<'tcx>) -> bool {
+        self.is_finalizer_safe(ty) && self.is_send(ty) && self.is_sync(ty)
     }
 
     fn check(&mut self, ty: Ty<'tcx>) {
@@ -116,12 +1083,19 @@ fn check(&mut self, ty: Ty<'tcx>) {
                 // None of these types can implement Drop.
                 return;
             }
-            ty::Dynamic(..) | ty::Error(..) => {
+            ty::Dynamic(..) => {
                 // Dropping a trait object uses a virtual call, so we can't
-                // work out which drop method to look at compile-time. This
-                // means we must be more conservative and bail with an error
-                // here, even if the drop impl itself would have been safe.
-                self.emit_err();
+                // work out which drop method to look at from its type alone.
+                // Trait objects whose bounds make them `Send + Sync +
+                // FinalizerSafe` (including via supertraits of the principal
+                // trait) were accepted above. Otherwise, the constructor call
+                // checks the concrete types which were unsized into it if it
+                // knows them, or must be conservative and bail with an error,
+                // even if the drop impl itself would have been safe.
+                self.errors.push(FinalizerError::UnknownDrop(self.tcx.erase_regions(ty)));
+            }
+            ty::Error(..) => {
+                // An error has already been reported for this type.
             }
             ty::Slice(ty) => self.check(*ty),
             ty::Array(elem_ty, ..) => {
@@ -133,22 +1107,20 @@ fn check(&mut self, ty: Ty<'tcx>) {
                 fields.iter().for_each(|f_ty| self.check(f_ty));
             }
             ty::Adt(def, substs) if !self.is_copy(ty) => {
-                if def.has_dtor(self.tcx) {
-                    if def.is_box() {
-                        // This is a special case because Box has an empty drop
-                        // method which is filled in later by the compiler.
-                        self.emit_err();
-                    }
+                if let Some(owned) = self.owned_values(*def, substs) {
+                    // The drop glue for these types drops the values they own
+                    // and then frees their memory through the allocator. Their
+                    // `Drop` impls are either empty placeholders (`Box`'s
+                    // contents are dropped by the compiler) or work through
+                    // raw pointers which we can't see through, so we check the
+                    // owned values directly instead.
+                    owned.into_iter().for_each(|ty| self.check(ty));
+                    return;
+                }
 
+                if def.has_dtor(self.tcx) {
                     let drop_trait = self.tcx.require_lang_item(LangItem::Drop, None);
-                    let drop_fn = self.tcx.associated_item_def_ids(drop_trait)[0];
-                    let substs = self.tcx.mk_args_trait(ty, substs.into_iter());
-                    let instance = ty::Instance::resolve(self.tcx, self.param_env, drop_fn, substs)
-                        .unwrap()
-                        .unwrap();
-                    let mir = self.tcx.instance_mir(instance.def);
-                    let mut checker = ProjectionChecker { cx: self, body: mir };
-                    checker.visit_body(&mir);
+                    self.check_method_body(drop_trait, ty, substs, "fn drop(&mut)");
                 }
 
                 for field in def.all_fields() {
@@ -156,6 +1128,29 @@ fn check(&mut self, ty: Ty<'tcx>) {
                     self.check(field_ty);
                 }
             }
+            ty::Closure(_, args) => {
+                // A closure is dropped by dropping each of its captured
+                // values.
+                args.as_closure().upvar_tys().iter().for_each(|upvar| self.check(upvar));
+            }
+            ty::CoroutineClosure(_, args) => {
+                args.as_coroutine_closure().upvar_tys().iter().for_each(|upvar| self.check(upvar));
+            }
+            ty::Coroutine(_, args) => {
+                // Coroutines are only checked in full once they have been
+                // borrow checked (see `CheckCoroutineFinalizers`), so their
+                // witnesses are available.
+                let args = args.as_coroutine();
+                args.upvar_tys().iter().for_each(|upvar| self.check(upvar));
+                self.check(args.witness());
+            }
+            ty::CoroutineWitness(def_id, args) => {
+                if let Some(witness) = self.tcx.mir_coroutine_witnesses(*def_id) {
+                    for field_ty in &witness.field_tys {
+                        self.check(ty::EarlyBinder::bind(field_ty.ty).instantiate(self.tcx, args));
+                    }
+                }
+            }
             _ => (),
         }
     }
@@ -170,21 +1165,15 @@ fn is_finalizer_safe(&self, ty: Ty<'tcx>) -> bool {
             .must_apply_modulo_regions();
     }
 
-    fn is_reference_free(&self, ty: Ty<'tcx>) -> bool {
-        let t = self.tcx.get_diagnostic_item(sym::ReferenceFree).unwrap();
-        return self
-            .tcx
-            .infer_ctxt()
-            .build()
-            .type_implements_trait(t, [ty], self.param_env)
-            .must_apply_modulo_regions();
-    }
-
     fn is_copy(&self, ty: Ty<'tcx>) -> bool {
         ty.is_copy_modulo_regions(self.tcx, self.param_env)
     }
 
     fn is_send(&self, ty: Ty<'tcx>) -> bool {
+        if self.kind == GcConstructorKind::Local {
+            // Local finalizers never leave the allocating thread.
+            return true;
+        }
         let t = self.tcx.get_diagnostic_item(sym::Send).unwrap();
         return self
             .tcx
@@ -195,6 +1184,11 @@ fn is_send(&self, ty: Ty<'tcx>) -> bool {
     }
 
     fn is_sync(&self, ty: Ty<'tcx>) -> bool {
+        if matches!(self.kind, GcConstructorKind::Local | GcConstructorKind::Unsynchronised) {
+            // The caller of `Gc::new_unsynchronised` has promised that the
+            // finalizer does not race with other threads.
+            return true;
+        }
         let t = self.tcx.get_diagnostic_item(sym::Sync).unwrap();
         return self
             .tcx
@@ -204,13 +1198,21 @@ fn is_sync(&self, ty: Ty<'tcx>) -> bool {
             .must_apply_modulo_regions();
     }
 
-    fn is_gc(&self, ty: Ty<'tcx>) -> bool {
-        if let ty::Adt(def, ..) = ty.kind() {
-            if def.did() == self.tcx.get_diagnostic_item(sym::gc).unwrap() {
-                return true;
-            }
+    /// For `Box`, `Vec` and `VecDeque`, returns the types of the values which
+    /// are dropped by their drop glue: the contents, and the allocator.
+    fn owned_values(
+        &self,
+        def: ty::AdtDef<'tcx>,
+        substs: GenericArgsRef<'tcx>,
+    ) -> Option<[Ty<'tcx>; 2]> {
+        let did = def.did();
+        if def.is_box()
+            || self.tcx.is_diagnostic_item(sym::Vec, did)
+            || self.tcx.is_diagnostic_item(sym::VecDeque, did)
+        {
+            return Some([substs.type_at(0), substs.type_at(1)]);
         }
-        return false;
+        None
     }
 
     fn is_finalize_unchecked(&self, ty: Ty<'tcx>) -> bool {
@@ -221,72 +1223,178 @@ fn is_finalize_unchecked(&self, ty: Ty<'tcx>) -> bool {
         }
         return false;
     }
+}
 
-    fn emit_err(&self) {
-        let arg = self.tcx.sess.source_map().span_to_snippet(self.arg).unwrap();
-        let mut err = self
-            .tcx
-            .sess
-            .psess
-            .dcx
-            .struct_span_err(self.arg, format!("`{arg}` cannot be safely finalized.",));
-        err.span_label(self.arg, "has a drop method which cannot be safely finalized.");
-        err.span_label(
-            self.ctor,
-            format!("`Gc::new` requires that it implements the `FinalizeSafe` trait.",),
-        );
-        err.help(format!("`Gc` runs finalizers on a separate thread, so `{arg}` must implement `FinalizeSafe` in order to be safely dropped.",));
-        err.emit();
-    }
-}
-
-struct ProjectionChecker<'a, 'tcx> {
-    cx: &'a FinalizationCtxt<'tcx>,
+/// A dataflow analysis which tracks the locals in a finalizing method, or a
+/// function which it calls, whose values may be derived from the object being
+/// finalized. These are the `roots` (i.e. the receiver), and anything copied,
+/// moved, borrowed or projected out of a derived local.
+///
+/// Only derived locals can reach the fields of the object being finalized, so
+/// values which are created on the finalizer thread (e.g. `let x = Self { ..
+/// }`) can be used freely.
+struct MaybeDerivedFromReceiver {
+    /// The arguments which are derived from the receiver on entry.
+    roots: BitSet<Local>,
+    /// Every local whose address is taken somewhere in the body. A write
+    /// through a pointer could target any of these, so if a derived value is
+    /// written through a pointer (or passed to a function, which could do the
+    /// same), they must all be assumed to be derived.
+    borrowed: BitSet<Local>,
+}
+
+impl MaybeDerivedFromReceiver {
+    fn new(body: &Body<'_>, roots: BitSet<Local>) -> Self {
+        MaybeDerivedFromReceiver { roots, borrowed: borrowed_locals(body) }
+    }
+}
+
+/// Finds whether a MIR fragment reads from any derived local, either directly
+/// or through a derived pointer.
+struct ReadsDerived<'a> {
+    derived: &'a BitSet<Local>,
+    found: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for ReadsDerived<'_> {
+    fn visit_local(&mut self, local: Local, _: PlaceContext, _: Location) {
+        self.found |= self.derived.contains(local);
+    }
+}
+
+impl<'tcx> AnalysisDomain<'tcx> for MaybeDerivedFromReceiver {
+    type Domain = BitSet<Local>;
+    const NAME: &'static str = "maybe_derived_from_receiver";
+
+    fn bottom_value(&self, body: &Body<'tcx>) -> Self::Domain {
+        BitSet::new_empty(body.local_decls().len())
+    }
+
+    fn initialize_start_block(&self, _: &Body<'tcx>, state: &mut Self::Domain) {
+        state.union(&self.roots);
+    }
+}
+
+impl<'tcx> Analysis<'tcx> for MaybeDerivedFromReceiver {
+    fn apply_statement_effect(
+        &mut self,
+        state: &mut Self::Domain,
+        statement: &Statement<'tcx>,
+        location: Location,
+    ) {
+        let StatementKind::Assign(box (place, rvalue)) = &statement.kind else {
+            return;
+        };
+        let mut reads = ReadsDerived { derived: state, found: false };
+        reads.visit_rvalue(rvalue, location);
+        let derived = reads.found;
+        if place.is_indirect() {
+            if derived {
+                state.union(&self.borrowed);
+            }
+        } else if derived {
+            state.insert(place.local);
+        } else if place.projection.is_empty() {
+            // The whole local is overwritten with an underived value.
+            state.remove(place.local);
+        }
+    }
+
+    fn apply_terminator_effect<'mir>(
+        &mut self,
+        state: &mut Self::Domain,
+        terminator: &'mir Terminator<'tcx>,
+        location: Location,
+    ) -> TerminatorEdges<'mir, 'tcx> {
+        if let TerminatorKind::Call { func, args, destination, .. } = &terminator.kind {
+            // A function which is passed a derived value may return it, or
+            // store it through any pointer which it was also passed. This
+            // is applied here rather than as a call return effect, since that
+            // doesn't know about the arguments. Doing so on the unwind edge
+            // too is merely conservative.
+            let mut reads = ReadsDerived { derived: state, found: false };
+            reads.visit_operand(func, location);
+            args.iter().for_each(|arg| reads.visit_operand(&arg.node, location));
+            if reads.found {
+                state.union(&self.borrowed);
+                if !destination.is_indirect() {
+                    state.insert(destination.local);
+                }
+            }
+        }
+        terminator.edges()
+    }
+
+    fn apply_call_return_effect(
+        &mut self,
+        _: &mut Self::Domain,
+        _: BasicBlock,
+        _: CallReturnPlaces<'_, 'tcx>,
+    ) {
+    }
+}
+
+/// Collects the uses of the object being finalized in a finalizing method, or
+/// a function which it calls: projections out of values derived from it, and
+/// calls which are passed such values.
+struct FinalizerUseCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
     body: &'a Body<'tcx>,
+    /// Which locals in `body` may refer to the object being finalized.
+    derived: ResultsCursor<'a, 'tcx, MaybeDerivedFromReceiver>,
+    uses: Vec<FinalizerUse<'tcx>>,
 }
 
-impl<'a, 'tcx> ProjectionChecker<'a, 'tcx> {
-    fn emit_err(&self, ty: Ty<'tcx>, span: Span) {
-        let arg = self.cx.tcx.sess.source_map().span_to_snippet(self.cx.arg).unwrap();
-        let mut err = self
-            .cx
-            .tcx
-            .sess
-            .psess
-            .dcx
-            .struct_span_err(self.cx.arg, format!("`{arg}` cannot be safely finalized.",));
-        if self.cx.is_gc(ty) {
-            err.span_label(self.cx.arg, "has a drop method which cannot be safely finalized.");
-            err.span_label(span, "caused by the expression here in `fn drop(&mut)` because");
-            err.span_label(span, "it uses another `Gc` type.");
-            err.help("`Gc` finalizers are unordered, so this field may have already been dropped. It is not safe to dereference.");
-        } else {
-            err.span_label(self.cx.arg, "has a drop method which cannot be safely finalized.");
-            err.span_label(span, "caused by the expression in `fn drop(&mut)` here because");
-            err.span_label(span, "it uses a type which is not safe to use in a finalizer.");
-            err.help("`Gc` runs finalizers on a separate thread, so drop methods\nmust only use values whose types implement `Send + Sync + FinalizerSafe`.");
-        }
-        err.emit();
-    }
-}
-
-impl<'a, 'tcx> Visitor<'tcx> for ProjectionChecker<'a, 'tcx> {
+impl<'a, 'tcx> FinalizerUseCollector<'a, 'tcx> {
+    fn collect(
+        tcx: TyCtxt<'tcx>,
+        body: &'a Body<'tcx>,
+        roots: BitSet<Local>,
+    ) -> Vec<FinalizerUse<'tcx>> {
+        let derived = MaybeDerivedFromReceiver::new(body, roots)
+            .into_engine(tcx, body)
+            .iterate_to_fixpoint()
+            .into_results_cursor(body);
+        let mut collector = FinalizerUseCollector { tcx, body, derived, uses: Vec::new() };
+        collector.visit_body(body);
+        collector.uses
+    }
+
+    fn is_derived(&mut self, local: Local, location: Location) -> bool {
+        self.derived.seek_before_primary_effect(location);
+        self.derived.get().contains(local)
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for FinalizerUseCollector<'a, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        // The built MIR mentions places for borrowck (e.g. `let _ = self.0`)
+        // without reading them, so these don't use the object.
+        match statement.kind {
+            StatementKind::FakeRead(..)
+            | StatementKind::PlaceMention(..)
+            | StatementKind::AscribeUserType(..) => {}
+            _ => self.super_statement(statement, location),
+        }
+    }
+
     fn visit_projection(
         &mut self,
         place_ref: PlaceRef<'tcx>,
         context: PlaceContext,
         location: Location,
     ) {
+        // Fields of values which don't come from the object being finalized
+        // were created on the finalizer thread, so are always safe to use.
+        if !self.is_derived(place_ref.local, location) {
+            self.super_projection(place_ref, context, location);
+            return;
+        }
         for (_, proj) in place_ref.iter_projections() {
             match proj {
                 ProjectionElem::Field(_, ty) => {
-                    if !self.cx.is_finalizer_safe(ty)
-                        || !self.cx.is_send(ty)
-                        || !self.cx.is_sync(ty)
-                    {
-                        let span = self.body.source_info(location).span;
-                        self.emit_err(ty, span);
-                    }
+                    let span = self.body.source_info(location).span;
+                    self.uses.push(FinalizerUse::Field { ty, span });
                 }
                 _ => (),
             }
@@ -295,50 +1403,23 @@ fn visit_projection(
     }
 
     fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
-        if let TerminatorKind::Call { ref args, .. } = terminator.kind {
-            for caller_arg in self.body.args_iter() {
-                let recv_ty = self.body.local_decls()[caller_arg].ty;
-                for arg in args.iter() {
-                    let arg_ty = arg.node.ty(self.body, self.cx.tcx);
-                    if arg_ty == recv_ty {
-                        // Currently, we do not recurse into function calls
-                        // to see whether they access `!FinalizerSafe`
-                        // fields, so we must throw an error in `drop`
-                        // methods which call other functions and pass
-                        // `self` as an argument.
-                        //
-                        // Here, we throw an error if `drop(&mut self)`
-                        // calls a function with an argument that has the
-                        // same type as the drop receiver (e.g. foo(x:
-                        // &Self)). This approximation will always prevent
-                        // unsound `drop` methods, however, it is overly
-                        // conservative and will prevent correct examples
-                        // like below from compiling:
-                        //
-                        // ```
-                        // fn drop(&mut self) {
-                        //   let x = Self { ... };
-                        //   x.foo();
-                        // }
-                        // ```
-                        //
-                        // This example is sound, because `x` is a local
-                        // that was instantiated on the finalizer thread, so
-                        // its fields are always safe to access from inside
-                        // this drop method.
-                        //
-                        // However, this will not compile, because the
-                        // receiver for `x.foo()` is the same type as the
-                        // `self` reference. To fix this, we would need to
-                        // do a def-use analysis on the self reference to
-                        // find every MIR local which refers to it that ends
-                        // up being passed to a call terminator. This is not
-                        // trivial to do at the moment.
-                        let span = self.body.source_info(location).span;
-                        self.emit_err(arg_ty, span);
-                    }
+        if let TerminatorKind::Call { ref func, ref args, .. } = terminator.kind {
+            let func = func.ty(self.body, self.tcx);
+            let span = self.body.source_info(location).span;
+            for (index, arg) in args.iter().enumerate() {
+                let derived = match arg.node.place() {
+                    Some(place) => self.is_derived(place.local, location),
+                    None => false,
+                };
+                if derived {
+                    let ty = arg.node.ty(self.body, self.tcx);
+                    let index = index as u32;
+                    self.uses.push(FinalizerUse::CallArg { func, index, ty, span });
                 }
             }
         }
+        // The arguments themselves may be projected out of the object (e.g.
+        // `f(self.0)`), which are field uses too.
+        self.super_terminator(terminator, location);
     }
 }
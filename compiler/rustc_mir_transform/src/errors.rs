@@ -1,10 +1,10 @@
 use rustc_errors::{codes::*, Diag, DiagMessage, LintDiagnostic};
 use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_middle::mir::AssertKind;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{Ty, TyCtxt};
 use rustc_session::lint::{self, Lint};
 use rustc_span::def_id::DefId;
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 use crate::fluent_generated as fluent;
 
@@ -37,6 +37,174 @@ pub(crate) struct UnalignedPackedRef {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(mir_transform_gc_invalid_constructor_arg)]
+pub(crate) struct GcInvalidConstructorArg {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_gc_dangling_reference, code = E0799)]
+pub(crate) struct GcDanglingReference {
+    #[label]
+    pub arg: Span,
+    pub arg_desc: String,
+    #[label(mir_transform_ctor_label)]
+    pub ctor: Span,
+    #[label(mir_transform_gc_ctor_instantiated)]
+    pub instantiated_ctor: Option<Span>,
+    #[note(mir_transform_gc_instantiated_here)]
+    pub instantiated_at: Option<Span>,
+    #[subdiagnostic]
+    pub field: Option<GcDanglingField>,
+    #[subdiagnostic]
+    pub new_unfinalizable: Option<GcNewUnfinalizable>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(mir_transform_gc_dangling_field)]
+pub(crate) struct GcDanglingField {
+    #[primary_span]
+    pub span: Span,
+    pub field: Symbol,
+    pub adt: String,
+}
+
+/// Only ever emitted at the `expect` level, to fulfil the expectation of a
+/// lint which can't be reported until after expectations are checked.
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_gc_check_deferred)]
+pub(crate) struct GcCheckDeferred;
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_gc_unknown_drop, code = E0798)]
+#[help]
+pub(crate) struct GcUnknownDrop {
+    #[label]
+    pub arg: Span,
+    pub arg_desc: String,
+    #[label(mir_transform_ctor_label)]
+    pub ctor: Span,
+    #[label(mir_transform_gc_ctor_instantiated)]
+    pub instantiated_ctor: Option<Span>,
+    #[note(mir_transform_gc_instantiated_here)]
+    pub instantiated_at: Option<Span>,
+    #[subdiagnostic]
+    pub new_unfinalizable: Option<GcNewUnfinalizable>,
+    #[subdiagnostic]
+    pub finalize_unchecked: Option<GcFinalizeUnchecked>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_gc_unsafe_use, code = E0798)]
+#[help]
+pub(crate) struct GcUnsafeUse {
+    #[label]
+    pub arg: Span,
+    pub arg_desc: String,
+    #[label(mir_transform_use_label)]
+    pub use_span: Span,
+    #[label(mir_transform_use_reason)]
+    pub use_reason: Span,
+    #[label(mir_transform_call_site_label)]
+    pub call_site: Option<Span>,
+    pub method: &'static str,
+    pub is_gc: bool,
+    #[label(mir_transform_gc_ctor_instantiated)]
+    pub instantiated_ctor: Option<Span>,
+    #[note(mir_transform_gc_instantiated_here)]
+    pub instantiated_at: Option<Span>,
+    #[subdiagnostic]
+    pub new_unfinalizable: Option<GcNewUnfinalizable>,
+    #[subdiagnostic]
+    pub finalize_unchecked: Option<GcFinalizeUnchecked>,
+    #[subdiagnostic]
+    pub finalizer_safe: Option<GcFinalizerSafe>,
+}
+
+#[derive(Subdiagnostic)]
+#[suggestion(
+    mir_transform_gc_new_unfinalizable,
+    code = "_unfinalizable",
+    applicability = "maybe-incorrect",
+    style = "verbose"
+)]
+pub(crate) struct GcNewUnfinalizable {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Subdiagnostic)]
+#[multipart_suggestion(
+    mir_transform_gc_finalize_unchecked,
+    applicability = "maybe-incorrect",
+    style = "verbose"
+)]
+pub(crate) struct GcFinalizeUnchecked {
+    #[suggestion_part(code = "unsafe {{ std::gc::FinalizeUnchecked::new(")]
+    pub lo: Span,
+    #[suggestion_part(code = ") }}")]
+    pub hi: Span,
+}
+
+#[derive(Subdiagnostic)]
+#[suggestion(
+    mir_transform_gc_finalizer_safe,
+    code = "\nunsafe impl std::marker::FinalizerSafe for {ty} {{}}",
+    applicability = "maybe-incorrect",
+    style = "verbose"
+)]
+pub(crate) struct GcFinalizerSafe {
+    #[primary_span]
+    pub span: Span,
+    pub ty: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_finalizer_optional_drop)]
+#[help]
+pub(crate) struct FinalizerOptionalDrop<'tcx> {
+    #[primary_span]
+    pub span: Span,
+    pub adt: Ty<'tcx>,
+    #[label(mir_transform_attr_label)]
+    pub attr: Span,
+    #[subdiagnostic]
+    pub violation: FinalizerOptionalViolation<'tcx>,
+}
+
+#[derive(Subdiagnostic)]
+pub(crate) enum FinalizerOptionalViolation<'tcx> {
+    #[label(mir_transform_finalizer_optional_call)]
+    Call {
+        #[primary_span]
+        span: Span,
+        callee: String,
+    },
+    #[label(mir_transform_finalizer_optional_indirect_call)]
+    IndirectCall {
+        #[primary_span]
+        span: Span,
+    },
+    #[label(mir_transform_finalizer_optional_drop_value)]
+    Drop {
+        #[primary_span]
+        span: Span,
+        ty: Ty<'tcx>,
+    },
+    #[label(mir_transform_finalizer_optional_asm)]
+    InlineAsm {
+        #[primary_span]
+        span: Span,
+    },
+    #[label(mir_transform_finalizer_optional_write)]
+    Write {
+        #[primary_span]
+        span: Span,
+    },
+}
+
 pub(crate) struct AssertLint<P> {
     pub span: Span,
     pub assert_kind: AssertKind<P>,
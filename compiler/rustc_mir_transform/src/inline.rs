@@ -731,6 +731,9 @@ fn dest_needs_borrow(place: Place<'_>) -> bool {
                 Const::Val(..) | Const::Unevaluated(..) => true,
             },
         ));
+        // The callee's Gc constructor calls have been instantiated for this call site, so they
+        // must be checked as part of the caller now.
+        caller_body.gc_constructor_calls.extend(callee_body.gc_constructor_calls.iter().cloned());
         // Now that we incorporated the callee's `required_consts`, we can remove the callee from
         // `mentioned_items` -- but we have to take their `mentioned_items` in return. This does
         // some extra work here to save the monomorphization collector work later. It helps a lot,
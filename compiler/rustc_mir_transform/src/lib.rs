@@ -52,6 +52,7 @@
 mod add_moves_for_packed_drops;
 mod add_retag;
 mod check_const_item_mutation;
+mod check_finalizer_optional;
 mod check_finalizers;
 mod check_packed_ref;
 mod remove_place_mention;
@@ -121,6 +122,7 @@
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
 pub fn provide(providers: &mut Providers) {
+    check_finalizers::provide(providers);
     coverage::query::provide(providers);
     ffi_unwind_calls::provide(providers);
     shim::provide(providers);
@@ -292,6 +294,7 @@ fn mir_built(tcx: TyCtxt<'_>, def: LocalDefId) -> &Steal<Body<'_>> {
             &Lint(check_packed_ref::CheckPackedRef),
             &Lint(check_const_item_mutation::CheckConstItemMutation),
             &Lint(function_item_references::FunctionItemReferences),
+            &Lint(check_finalizer_optional::CheckFinalizerOptional),
             // If this is an async closure's output coroutine, generate
             // by-move and by-mut bodies if needed. We do this first so
             // they can be optimized in lockstep with their parent bodies.
@@ -330,6 +333,13 @@ fn mir_promoted(
     };
     // has_ffi_unwind_calls query uses the raw mir, so make sure it is run.
     tcx.ensure_with_value().has_ffi_unwind_calls(def);
+    // finalizer_uses query uses the raw mir too, and may be needed for this
+    // body (or others which it calls) once it has been stolen.
+    if matches!(tcx.def_kind(def), DefKind::Fn | DefKind::AssocFn | DefKind::Closure)
+        && tcx.get_diagnostic_item(sym::gc).is_some()
+    {
+        tcx.ensure_with_value().finalizer_uses(def.to_def_id());
+    }
     let mut body = tcx.mir_built(def).steal();
     if let Some(error_reported) = const_qualifs.tainted_by_errors {
         body.tainted_by_errors = Some(error_reported);
@@ -501,6 +511,7 @@ pub fn run_analysis_to_runtime_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'
 /// After this series of passes, no lifetime analysis based on borrowing can be done.
 fn run_analysis_cleanup_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
     let passes: &[&dyn MirPass<'tcx>] = &[
+        &check_finalizers::CheckCoroutineFinalizers,
         &cleanup_post_borrowck::CleanupPostBorrowck,
         &remove_noop_landing_pads::RemoveNoopLandingPads,
         &simplify::SimplifyCfg::PostAnalysis,
@@ -1,5 +1,6 @@
+use rustc_hir::def_id::CrateNum;
 use rustc_middle::mir::*;
-use rustc_middle::ty::{self, TyCtxt};
+use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_span::sym;
 
 use super::simplify::simplify_cfg;
@@ -10,10 +11,6 @@ impl<'tcx> MirPass<'tcx> for RemoveGcDrops {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         trace!("Running RemoveGcDrops on {:?}", body.source);
 
-        let is_gc_crate = tcx
-            .get_diagnostic_item(sym::gc)
-            .map_or(false, |gc| gc.krate == body.source.def_id().krate);
-
         let did = body.source.def_id();
         let param_env = tcx.param_env_reveal_all_normalized(did);
         let mut should_simplify = false;
@@ -27,7 +24,7 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
                 }
 
                 if let ty::Adt(_, substs) = ty.kind() {
-                    if is_gc_crate || !substs.type_at(0).needs_finalizer(tcx, param_env) {
+                    if elides_gc_drop(tcx, param_env, substs.type_at(0), did.krate) {
                         terminator.kind = TerminatorKind::Goto { target };
                         should_simplify = true;
                     }
@@ -41,3 +38,15 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         }
     }
 }
+
+/// Returns whether drops of a `Gc` whose value has type `value_ty` are removed
+/// from bodies in `krate`.
+pub(crate) fn elides_gc_drop<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    value_ty: Ty<'tcx>,
+    krate: CrateNum,
+) -> bool {
+    let is_gc_crate = tcx.get_diagnostic_item(sym::gc).map_or(false, |gc| gc.krate == krate);
+    is_gc_crate || !value_ty.needs_finalizer(tcx, param_env)
+}
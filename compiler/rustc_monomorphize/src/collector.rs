@@ -447,6 +447,7 @@ fn collect_items_rec<'tcx>(
                 collect_items_of_instance(
                     tcx,
                     instance,
+                    starting_item.span,
                     &mut used_items,
                     &mut mentioned_items,
                     mode,
@@ -1250,10 +1251,11 @@ fn assoc_fn_of_type<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, fn_ident: Ident) ->
 /// Scans the MIR in order to find function calls, closures, and drop-glue.
 ///
 /// Anything that's found is added to `output`. Furthermore the "mentioned items" of the MIR are returned.
-#[instrument(skip(tcx, used_items, mentioned_items), level = "debug")]
+#[instrument(skip(tcx, span, used_items, mentioned_items), level = "debug")]
 fn collect_items_of_instance<'tcx>(
     tcx: TyCtxt<'tcx>,
     instance: Instance<'tcx>,
+    span: Span,
     used_items: &mut MonoItems<'tcx>,
     mentioned_items: &mut MonoItems<'tcx>,
     mode: CollectionMode,
@@ -1284,6 +1286,12 @@ fn collect_items_of_instance<'tcx>(
         for (bb, data) in traversal::mono_reachable(body, tcx, instance) {
             collector.visit_basic_block_data(bb, data)
         }
+
+        // Gc constructor calls in generic code could only be checked against the bounds in scope,
+        // so check them again now that the types of the values they finalize are known.
+        for call in &body.gc_constructor_calls {
+            tcx.at(span).check_mono_gc_constructor_call(collector.monomorphize(call.clone()));
+        }
     }
 
     // Always visit all `required_consts`, so that we evaluate them and abort compilation if any of
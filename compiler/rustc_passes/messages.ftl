@@ -320,6 +320,10 @@ passes_ffi_const_invalid_target =
 passes_ffi_pure_invalid_target =
     `#[ffi_pure]` may only be used on foreign functions
 
+passes_finalizer_optional =
+    `finalizer_optional` attribute should be applied to a struct, enum, or union
+    .label = is not a struct, enum, or union
+
 passes_has_incoherent_inherent_impl =
     `rustc_has_incoherent_inherent_impls` attribute should be applied to types or traits.
     .label = only adts, extern types and traits are supported
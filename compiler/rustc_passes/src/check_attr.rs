@@ -182,6 +182,7 @@ fn check_attributes(
                 }
                 sym::collapse_debuginfo => self.check_collapse_debuginfo(attr, span, target),
                 sym::must_not_suspend => self.check_must_not_suspend(attr, span, target),
+                sym::finalizer_optional => self.check_finalizer_optional(attr, span, target),
                 sym::must_use => self.check_must_use(hir_id, attr, target),
                 sym::rustc_pass_by_value => self.check_pass_by_value(attr, span, target),
                 sym::rustc_allow_incoherent_impl => {
@@ -1355,6 +1356,17 @@ fn check_must_not_suspend(&self, attr: &Attribute, span: Span, target: Target) -
         }
     }
 
+    /// Checks if `#[finalizer_optional]` is applied to a struct, enum, or union.
+    fn check_finalizer_optional(&self, attr: &Attribute, span: Span, target: Target) -> bool {
+        match target {
+            Target::Struct | Target::Enum | Target::Union => true,
+            _ => {
+                self.dcx().emit_err(errors::FinalizerOptional { attr_span: attr.span, span });
+                false
+            }
+        }
+    }
+
     /// Checks if `#[cold]` is applied to a non-function. Returns `true` if valid.
     fn check_cold(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Target) {
         match target {
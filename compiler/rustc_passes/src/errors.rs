@@ -413,6 +413,15 @@ pub struct MustNotSuspend {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_finalizer_optional)]
+pub struct FinalizerOptional {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub span: Span,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(passes_cold)]
 #[warning]
@@ -89,10 +89,31 @@ pub struct VTableSizeInfo {
     pub upcasting_cost_percent: f64,
 }
 
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct GcFinalizerInfo {
+    /// The file in which the `Gc` is allocated.
+    pub file: String,
+    /// The line and column at which the allocation starts.
+    pub lo: (usize, usize),
+    /// The line and column at which the allocation ends.
+    pub hi: (usize, usize),
+    /// The type of the value moved into the `Gc`.
+    pub type_description: String,
+    /// Whether a finalizer is registered for the allocation.
+    pub registered: bool,
+    /// Whether drops of the `Gc` are removed, because its value is never
+    /// finalized.
+    pub drop_elided: bool,
+    /// Why the value needs a finalizer, from its type down to the component
+    /// type which forced it. Empty if no finalizer is registered.
+    pub reasons: Vec<String>,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
     vtable_sizes: Lock<FxHashMap<DefId, VTableSizeInfo>>,
+    gc_finalizers: Lock<FxHashSet<GcFinalizerInfo>>,
 }
 
 impl CodeStats {
@@ -134,6 +155,10 @@ pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTabl
         );
     }
 
+    pub fn record_gc_finalizer(&self, info: GcFinalizerInfo) {
+        self.gc_finalizers.lock().insert(info);
+    }
+
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
         // We will soon sort, so the initial order does not matter.
@@ -266,4 +291,37 @@ pub fn print_vtable_sizes(&self, crate_name: Symbol) {
             );
         }
     }
+
+    pub fn print_gc_finalizers(&self) {
+        let gc_finalizers = self.gc_finalizers.lock();
+        // We will soon sort, so the initial order does not matter.
+        #[allow(rustc::potential_query_instability)]
+        let mut infos: Vec<_> = gc_finalizers.iter().collect();
+        infos.sort_by(|a, b| {
+            (&a.file, a.lo, a.hi, &a.type_description).cmp(&(
+                &b.file,
+                b.lo,
+                b.hi,
+                &b.type_description,
+            ))
+        });
+
+        for info in infos {
+            let yes_no = |b| if b { "yes" } else { "no" };
+            let ((lo_line, lo_col), (hi_line, hi_col)) = (info.lo, info.hi);
+            println!(
+                "print-gc-finalizers site: {}:{lo_line}:{lo_col}: {hi_line}:{hi_col}",
+                info.file
+            );
+            println!("print-gc-finalizers     type: `{}`", info.type_description);
+            println!("print-gc-finalizers     finalizer registered: {}", yes_no(info.registered));
+            println!("print-gc-finalizers     `Gc` drops elided: {}", yes_no(info.drop_elided));
+            if !info.reasons.is_empty() {
+                println!("print-gc-finalizers     needs a finalizer because:");
+                for reason in &info.reasons {
+                    println!("print-gc-finalizers         {reason}");
+                }
+            }
+        }
+    }
 }
@@ -315,6 +315,7 @@ pub fn create_feature_err<'a>(&'a self, err: impl Diagnostic<'a>, feature: Symbo
     /// checking about whether its cost was justified.
     pub fn record_trimmed_def_paths(&self) {
         if self.opts.unstable_opts.print_type_sizes
+            || self.opts.unstable_opts.print_gc_finalizers
             || self.opts.unstable_opts.query_dep_graph
             || self.opts.unstable_opts.dump_mir.is_some()
             || self.opts.unstable_opts.unpretty.is_some()
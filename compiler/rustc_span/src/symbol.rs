@@ -376,6 +376,7 @@
         alloc_zeroed,
         allocator,
         allocator_api,
+        allocator_deallocate,
         allocator_internals,
         allow,
         allow_fail,
@@ -842,6 +843,7 @@
         field,
         field_init_shorthand,
         file,
+        finalize,
         finalizer_optional,
         float,
         float_to_int_unchecked,
@@ -902,7 +904,6 @@
         future,
         future_trait,
         gc,
-        gc_ctor,
         gdb_script_file,
         ge,
         gen_blocks,
@@ -1579,6 +1580,7 @@
         rustc_error,
         rustc_evaluate_where_clauses,
         rustc_expected_cgu_reuse,
+        rustc_gc_constructor,
         rustc_has_incoherent_inherent_impls,
         rustc_hidden_type_of_opaques,
         rustc_if_this_changed,
@@ -1916,6 +1918,7 @@
         unaligned_volatile_load,
         unaligned_volatile_store,
         unboxed_closures,
+        unchecked,
         unchecked_add,
         unchecked_div,
         unchecked_mul,
@@ -1926,6 +1929,7 @@
         underscore_const_names,
         underscore_imports,
         underscore_lifetimes,
+        unfinalized,
         uniform_paths,
         unimplemented_macro,
         unit,
@@ -1957,6 +1961,7 @@
         unstable_location_reason_default: "this crate is being loaded from the sysroot, an \
                           unstable location; did you mean to load this crate \
                           from crates.io via `Cargo.toml` instead?",
+        unsynchronised,
         untagged_unions,
         unused_imports,
         unwind,
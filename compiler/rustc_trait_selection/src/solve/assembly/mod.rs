@@ -153,6 +153,13 @@ fn consider_builtin_pointer_like_candidate(
         goal: Goal<'tcx, Self>,
     ) -> QueryResult<'tcx>;
 
+    /// A type is `Unfinalized` if `needs_finalizer_raw` says that it never
+    /// needs a finalizer.
+    fn consider_builtin_unfinalized_candidate(
+        ecx: &mut EvalCtxt<'_, 'tcx>,
+        goal: Goal<'tcx, Self>,
+    ) -> QueryResult<'tcx>;
+
     /// A type is a `FnPtr` if it is of `FnPtr` type.
     fn consider_builtin_fn_ptr_trait_candidate(
         ecx: &mut EvalCtxt<'_, 'tcx>,
@@ -496,6 +503,8 @@ fn assemble_builtin_impl_candidates<G: GoalKind<'tcx>>(
             G::consider_builtin_copy_clone_candidate(self, goal)
         } else if lang_items.pointer_like() == Some(trait_def_id) {
             G::consider_builtin_pointer_like_candidate(self, goal)
+        } else if lang_items.unfinalized_trait() == Some(trait_def_id) {
+            G::consider_builtin_unfinalized_candidate(self, goal)
         } else if lang_items.fn_ptr_trait() == Some(trait_def_id) {
             G::consider_builtin_fn_ptr_trait_candidate(self, goal)
         } else if let Some(kind) = self.tcx().fn_trait_kind_from_def_id(trait_def_id) {
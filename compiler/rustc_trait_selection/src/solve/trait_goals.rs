@@ -247,6 +247,32 @@ fn consider_builtin_pointer_like_candidate(
         }
     }
 
+    fn consider_builtin_unfinalized_candidate(
+        ecx: &mut EvalCtxt<'_, 'tcx>,
+        goal: Goal<'tcx, Self>,
+    ) -> QueryResult<'tcx> {
+        if goal.predicate.polarity != ty::PredicatePolarity::Positive {
+            return Err(NoSolution);
+        }
+
+        // Whether a type needs a finalizer doesn't depend on its regions.
+        let tcx = ecx.tcx();
+        let key = tcx.erase_regions(goal.param_env.and(goal.predicate.self_ty()));
+        if key.has_non_region_infer() {
+            return ecx.evaluate_added_goals_and_make_canonical_response(Certainty::AMBIGUOUS);
+        }
+
+        match key.value.kind() {
+            // These are only `Unfinalized` if the param-env says so, which is
+            // handled by the param-env candidates.
+            ty::Alias(..) | ty::Param(_) | ty::Placeholder(_) | ty::Bound(..) => Err(NoSolution),
+            _ if !tcx.needs_finalizer_raw(key) => {
+                ecx.evaluate_added_goals_and_make_canonical_response(Certainty::Yes)
+            }
+            _ => Err(NoSolution),
+        }
+    }
+
     fn consider_builtin_fn_ptr_trait_candidate(
         ecx: &mut EvalCtxt<'_, 'tcx>,
         goal: Goal<'tcx, Self>,
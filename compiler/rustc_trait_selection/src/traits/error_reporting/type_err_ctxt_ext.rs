@@ -595,6 +595,14 @@ fn report_selection_error(
                             );
                         }
 
+                        if Some(trait_ref.def_id()) == tcx.lang_items().unfinalized_trait() {
+                            self.add_unfinalized_trait_message(
+                                obligation.param_env,
+                                trait_ref,
+                                &mut err,
+                            );
+                        }
+
                         if Some(trait_ref.def_id()) == tcx.lang_items().drop_trait()
                             && predicate_is_const
                         {
@@ -3186,6 +3194,25 @@ fn add_tuple_trait_message(
         }
     }
 
+    /// Names the component which stops a type from being `Unfinalized`.
+    fn add_unfinalized_trait_message(
+        &self,
+        param_env: ty::ParamEnv<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        err: &mut Diag<'_>,
+    ) {
+        let self_ty = self.tcx.instantiate_bound_regions_with_erased(trait_ref.self_ty());
+        let self_ty = self.tcx.erase_regions(self.resolve_vars_if_possible(self_ty));
+        // A type parameter is already pointed at by the suggestion to
+        // constrain it.
+        if self_ty.has_non_region_infer() || self_ty.is_ty_param() {
+            return;
+        }
+        for reason in self_ty.finalizer_reasons(self.tcx, param_env) {
+            err.note(reason);
+        }
+    }
+
     fn try_to_add_help_message(
         &self,
         obligation: &PredicateObligation<'tcx>,
@@ -109,6 +109,8 @@ pub(super) fn assemble_candidates<'o>(
                 self.assemble_candidate_for_tuple(obligation, &mut candidates);
             } else if lang_items.pointer_like() == Some(def_id) {
                 self.assemble_candidate_for_pointer_like(obligation, &mut candidates);
+            } else if lang_items.unfinalized_trait() == Some(def_id) {
+                self.assemble_candidate_for_unfinalized(obligation, &mut candidates);
             } else if lang_items.fn_ptr_trait() == Some(def_id) {
                 self.assemble_candidates_for_fn_ptr_trait(obligation, &mut candidates);
             } else {
@@ -1320,6 +1322,33 @@ fn assemble_candidate_for_pointer_like(
         }
     }
 
+    fn assemble_candidate_for_unfinalized(
+        &mut self,
+        obligation: &PolyTraitObligation<'tcx>,
+        candidates: &mut SelectionCandidateSet<'tcx>,
+    ) {
+        // Whether a type needs a finalizer doesn't depend on its regions.
+        let tcx = self.tcx();
+        let self_ty = tcx.instantiate_bound_regions_with_erased(obligation.predicate.self_ty());
+        let key = tcx.erase_regions(obligation.param_env.and(self_ty));
+        if key.has_non_region_infer() {
+            candidates.ambiguous = true;
+            return;
+        }
+
+        match key.value.kind() {
+            // These are only `Unfinalized` if the param-env says so.
+            // `needs_finalizer_raw` asks us about them in turn, so
+            // answering here would be a cycle.
+            ty::Alias(..) | ty::Param(_) | ty::Placeholder(_) | ty::Bound(..) => {}
+            _ => {
+                if !tcx.needs_finalizer_raw(key) {
+                    candidates.vec.push(BuiltinCandidate { has_nested: false });
+                }
+            }
+        }
+    }
+
     fn assemble_candidates_for_fn_ptr_trait(
         &mut self,
         obligation: &PolyTraitObligation<'tcx>,
@@ -26,6 +26,14 @@ fn finalizer_optional_raw<'tcx>(tcx: TyCtxt<'tcx>, query: ty::ParamEnvAnd<'tcx,
     is_item_raw(tcx, query, LangItem::FinalizerOptional)
 }
 
+fn is_finalize_raw<'tcx>(tcx: TyCtxt<'tcx>, query: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
+    is_item_raw(tcx, query, LangItem::Finalize)
+}
+
+fn is_unfinalized_raw<'tcx>(tcx: TyCtxt<'tcx>, query: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
+    is_item_raw(tcx, query, LangItem::Unfinalized)
+}
+
 fn is_item_raw<'tcx>(
     tcx: TyCtxt<'tcx>,
     query: ty::ParamEnvAnd<'tcx, Ty<'tcx>>,
@@ -44,6 +52,8 @@ pub(crate) fn provide(providers: &mut Providers) {
         is_freeze_raw,
         is_unpin_raw,
         finalizer_optional_raw,
+        is_finalize_raw,
+        is_unfinalized_raw,
         ..*providers
     };
 }
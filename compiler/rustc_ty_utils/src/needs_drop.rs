@@ -227,6 +227,12 @@ fn next(&mut self) -> Option<NeedsDropResult<Ty<'tcx>>> {
                             queue_type(self, required);
                         }
                     }
+                    // A bound such as `T: Unfinalized` guarantees that the
+                    // type never needs a finalizer, whatever it turns out to be.
+                    ty::Alias(..) | ty::Placeholder(_) | ty::Param(_)
+                        if self.analysis_kind.is_finalization()
+                            && component.is_unfinalized(tcx, self.param_env) => {}
+
                     ty::Alias(..) | ty::Array(..) | ty::Placeholder(_) | ty::Param(_) => {
                         if ty == component {
                             // Return the type to the caller: they may be able
@@ -451,6 +457,16 @@ fn adt_significant_drop_tys(
 }
 
 fn needs_finalizer_raw<'tcx>(tcx: TyCtxt<'tcx>, query: ty::ParamEnvAnd<'tcx, Ty<'tcx>>) -> bool {
+    // A `Finalize` impl replaces the drop glue of the value inside a `Gc`, so
+    // it always needs a finalizer, regardless of whether the type or its
+    // fields have any drop glue. This only applies to the outermost type:
+    // `Finalize` impls on field types are ignored, since fields are dropped
+    // as normal.
+    if query.value.is_finalize(tcx, query.param_env) {
+        debug!("needs_finalizer_raw({:?}) = true (implements `Finalize`)", query);
+        return true;
+    }
+
     let adt_has_dtor =
         |adt_def: ty::AdtDef<'tcx>| adt_def.destructor(tcx).map(|_| DtorType::Significant);
 
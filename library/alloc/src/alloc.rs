@@ -114,6 +114,7 @@ pub unsafe fn alloc(layout: Layout) -> *mut u8 {
 ///
 /// See [`GlobalAlloc::dealloc`].
 #[stable(feature = "global_alloc", since = "1.28.0")]
+#[cfg_attr(not(test), rustc_diagnostic_item = "dealloc")]
 #[inline]
 pub unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
     unsafe { __rust_dealloc(ptr, layout.size(), layout.align()) }
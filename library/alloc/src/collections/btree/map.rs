@@ -3,6 +3,7 @@
 use core::cmp::Ordering;
 use core::error::Error;
 use core::fmt::{self, Debug};
+use core::gc::FinalizerOptional;
 use core::hash::{Hash, Hasher};
 use core::iter::FusedIterator;
 use core::marker::PhantomData;
@@ -183,6 +184,9 @@ pub struct BTreeMap<
     _marker: PhantomData<crate::boxed::Box<(K, V), A>>,
 }
 
+#[unstable(feature = "gc", issue = "none")]
+unsafe impl<K, V, A: Allocator + Clone> FinalizerOptional for BTreeMap<K, V, A> {}
+
 #[stable(feature = "btree_drop", since = "1.7.0")]
 unsafe impl<#[may_dangle] K, #[may_dangle] V, A: Allocator + Clone> Drop for BTreeMap<K, V, A> {
     fn drop(&mut self) {
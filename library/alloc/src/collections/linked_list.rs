@@ -14,6 +14,7 @@
 
 use core::cmp::Ordering;
 use core::fmt;
+use core::gc::FinalizerOptional;
 use core::hash::{Hash, Hasher};
 use core::iter::FusedIterator;
 use core::marker::PhantomData;
@@ -1166,6 +1167,9 @@ pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F, A>
     }
 }
 
+#[unstable(feature = "gc", issue = "none")]
+unsafe impl<T, A: Allocator> FinalizerOptional for LinkedList<T, A> {}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 unsafe impl<#[may_dangle] T, A: Allocator> Drop for LinkedList<T, A> {
     fn drop(&mut self) {
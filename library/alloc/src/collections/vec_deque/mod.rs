@@ -9,6 +9,7 @@
 
 use core::cmp::{self, Ordering};
 use core::fmt;
+use core::gc::FinalizerOptional;
 use core::hash::{Hash, Hasher};
 use core::iter::{repeat_n, repeat_with, ByRefSized};
 use core::mem::{ManuallyDrop, SizedTypeProperties};
@@ -123,6 +124,9 @@ fn clone_from(&mut self, source: &Self) {
     }
 }
 
+#[unstable(feature = "gc", issue = "none")]
+unsafe impl<T, A: Allocator> FinalizerOptional for VecDeque<T, A> {}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 unsafe impl<#[may_dangle] T, A: Allocator> Drop for VecDeque<T, A> {
     fn drop(&mut self) {
@@ -34,6 +34,8 @@ pub struct ProfileStats {
 extern "C" {
     pub fn GC_malloc(nbytes: usize) -> *mut u8;
 
+    pub fn GC_malloc_uncollectable(nbytes: usize) -> *mut u8;
+
     pub fn GC_posix_memalign(mem_ptr: *mut *mut u8, align: usize, nbytes: usize) -> i32;
 
     pub fn GC_realloc(old: *mut u8, new_size: usize) -> *mut u8;
@@ -46,7 +48,7 @@ pub fn GC_register_finalizer(
         ptr: *mut u8,
         finalizer: Option<unsafe extern "C" fn(*mut u8, *mut u8)>,
         client_data: *mut u8,
-        old_finalizer: *mut extern "C" fn(*mut u8, *mut u8),
+        old_finalizer: *mut Option<unsafe extern "C" fn(*mut u8, *mut u8)>,
         old_client_data: *mut *mut u8,
     );
 
@@ -54,7 +56,7 @@ pub fn GC_register_finalizer_no_order(
         ptr: *mut u8,
         finalizer: Option<unsafe extern "C" fn(*mut u8, *mut u8)>,
         client_data: *mut u8,
-        old_finalizer: *mut extern "C" fn(*mut u8, *mut u8),
+        old_finalizer: *mut Option<unsafe extern "C" fn(*mut u8, *mut u8)>,
         old_client_data: *mut *mut u8,
     );
 
@@ -88,4 +90,14 @@ pub fn GC_pthread_create(
     pub fn GC_get_gc_no() -> u64;
 
     pub fn GC_keep_alive(ptr: *mut u8);
+
+    pub fn GC_set_start_callback(callback: Option<extern "C" fn()>);
+
+    pub fn GC_set_await_finalize_proc(callback: Option<extern "C" fn(*mut u8)>);
+
+    pub fn GC_set_finalize_on_demand(value: i32);
+
+    pub fn GC_set_finalizer_notifier(notifier: Option<extern "C" fn()>);
+
+    pub fn GC_invoke_finalizers() -> i32;
 }
@@ -9,10 +9,73 @@
 ///
 /// Unsafe because this should be used with care. Preventing drop from
 /// running can lead to surprising behaviour.
+///
+/// Where a type's `Drop` impl only frees memory and drops its fields, prefer
+/// marking the type `#[finalizer_optional]` (under the feature of the same
+/// name). This has the same effect, but the compiler checks the `Drop` impl
+/// instead of trusting it.
 #[rustc_diagnostic_item = "finalizer_optional"]
 #[cfg_attr(not(bootstrap), lang = "finalizer_optional")]
 pub unsafe trait FinalizerOptional {}
 
+/// Types which never need a finalizer when they are owned by a `Gc`.
+///
+/// This trait is implemented automatically by the compiler for every type
+/// for which [`needs_finalizer`] returns `false`: that is, types which neither
+/// implement [`Finalize`] nor own any component with drop glue which must be
+/// run by the collector. It cannot be implemented manually.
+///
+/// Unlike [`needs_finalizer`], `Unfinalized` can be used as a bound, so generic
+/// code can guarantee that allocating a `Gc` never registers a finalizer:
+///
+/// ```
+/// #![feature(gc)]
+/// use std::gc::{Gc, Unfinalized};
+///
+/// fn alloc<T: Unfinalized>(value: T) -> Gc<T> {
+///     Gc::new(value)
+/// }
+///
+/// alloc((1, "one", [2u8; 4]));
+/// ```
+///
+/// [`needs_finalizer`]: crate::mem::needs_finalizer
+#[cfg_attr(not(bootstrap), lang = "unfinalized")]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` needs a finalizer",
+    label = "`{Self}` needs a finalizer when owned by a `Gc`"
+)]
+#[rustc_deny_explicit_impl(implement_via_object = false)]
+pub trait Unfinalized {}
+
+/// Custom finalization logic for values owned by a `Gc`.
+///
+/// By default, when a `Gc<T>` is collected its value is finalized by running
+/// its drop glue, so every [`Drop`] impl reachable from `T` must be safe to run
+/// on a finalizer thread. If `T` implements `Finalize`, the collector instead
+/// calls [`finalize`] on the value, and does not run its drop glue at all.
+/// This keeps deterministic destruction (`Drop`) and garbage collection
+/// finalization separate: finalizer safety analysis only checks `finalize`,
+/// and `Drop` impls for values which live on the stack are unrestricted.
+///
+/// Fields of `T` are *not* dropped after `finalize` returns. Any resources
+/// they own which are not managed by the collector must be released by
+/// `finalize` itself, or they will be leaked.
+///
+/// `Finalize` only affects the value directly inside the `Gc`. When a type
+/// which implements `Finalize` is a field of another type, it is dropped as
+/// normal.
+///
+/// [`Drop`]: crate::ops::Drop
+/// [`finalize`]: Finalize::finalize
+#[cfg_attr(not(bootstrap), lang = "finalize")]
+#[rustc_specialization_trait]
+pub trait Finalize {
+    /// Called on the finalizer thread once the `Gc` which owns this value has
+    /// become unreachable.
+    fn finalize(&mut self);
+}
+
 /// A wrapper which prevents `T` from being finalized when used in a `Gc`.
 ///
 /// This is useful for when its not possible to implement `FinalizerOptional`
@@ -1477,7 +1477,7 @@ pub const fn unlikely(b: bool) -> bool {
     #[rustc_safe_intrinsic]
     #[rustc_nounwind]
     #[cfg(not(bootstrap))]
-    pub fn needs_finalizer<T>() -> bool;
+    pub fn needs_finalizer<T: ?Sized>() -> bool;
 
     /// Calculates the offset from a pointer.
     ///
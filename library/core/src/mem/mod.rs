@@ -613,7 +613,7 @@ pub const fn needs_drop<T: ?Sized>() -> bool {
 #[unstable(feature = "gc", issue = "none")]
 #[rustc_const_unstable(feature = "gc", issue = "none")]
 #[cfg(not(bootstrap))]
-pub const fn needs_finalizer<T>() -> bool {
+pub const fn needs_finalizer<T: ?Sized>() -> bool {
     intrinsics::needs_finalizer::<T>()
 }
 
@@ -44,17 +44,25 @@
     fmt,
     hash::{Hash, Hasher},
     marker::Unsize,
-    mem::{align_of_val_raw, MaybeUninit},
+    mem::{self, MaybeUninit},
     ops::{CoerceUnsized, Deref, DispatchFromDyn, Receiver},
-    ptr::{self, drop_in_place, NonNull},
+    panic::{RefUnwindSafe, UnwindSafe},
+    pin::Pin,
+    ptr::{self, drop_in_place, NonNull, Pointee},
 };
 
+use crate::alloc::handle_alloc_error;
+
 pub use core::gc::*;
 
-#[cfg(profile_gc)]
-use core::sync::atomic::{self, AtomicU64};
+use core::sync::atomic::{self, AtomicBool, AtomicU64, AtomicU8, AtomicUsize};
 
-use core::sync::atomic::{self, AtomicU64};
+use crate::collections::HashMap;
+use crate::io;
+use crate::panic::{catch_unwind, AssertUnwindSafe};
+use crate::sync::{Arc, Condvar, Mutex, OnceLock, RwLock};
+use crate::thread;
+use crate::time::{Duration, Instant};
 
 #[cfg(test)]
 mod tests;
@@ -81,11 +89,6 @@ pub struct GcStats {
     allocated_normal: AtomicU64::new(0),
 };
 
-#[cfg(profile_gc)]
-static FINALIZERS_REGISTERED: AtomicU64 = AtomicU64::new(0);
-#[cfg(profile_gc)]
-static FINALIZERS_COMPLETED: AtomicU64 = AtomicU64::new(0);
-
 ////////////////////////////////////////////////////////////////////////////////
 // BDWGC Allocator
 ////////////////////////////////////////////////////////////////////////////////
@@ -195,6 +198,17 @@ pub fn stats() -> GcStats {
 pub fn init() {
     unsafe { bdwgc::GC_set_markers_count(1) }
     unsafe { bdwgc::GC_init() }
+    if crate::env::var_os("RUST_GC_PROFILE").is_some_and(|v| v != "0") {
+        enable_finalizer_profiling();
+        PRINT_PROFILE_AT_EXIT.store(true, atomic::Ordering::Relaxed);
+    }
+}
+
+/// One-time GC cleanup, run by the runtime after `main` returns.
+pub(crate) fn cleanup() {
+    if PRINT_PROFILE_AT_EXIT.load(atomic::Ordering::Relaxed) {
+        print_finalizer_profile();
+    }
 }
 
 pub fn suppress_warnings() {
@@ -209,15 +223,981 @@ pub fn keep_alive<T>(ptr: *mut T) {
     unsafe { bdwgc::GC_keep_alive(ptr as *mut u8) }
 }
 
+/// Registers `finalizer` to be run on the object at `ptr` once it becomes
+/// unreachable. `client_data` is passed through to the finalizer untouched.
+///
+/// # Safety
+///
+/// `ptr` must be the base of an object allocated by the collector, and
+/// `finalizer` must be safe to call with `ptr` and `client_data` on the
+/// finalizer thread.
+unsafe fn register_finalizer<T: ?Sized>(
+    ptr: *mut u8,
+    finalizer: unsafe extern "C" fn(*mut u8, *mut u8),
+    client_data: *mut u8,
+) {
+    unsafe {
+        bdwgc::GC_register_finalizer_no_order(
+            ptr,
+            Some(finalizer),
+            client_data,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+    }
+    GC_COUNTERS.finalizers_registered.fetch_add(1, atomic::Ordering::Relaxed);
+    if profiling_enabled() {
+        with_type_profile(core::any::type_name::<T>(), |p| p.registered += 1);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Finalizer panics
+////////////////////////////////////////////////////////////////////////////////
+
+/// What to do when a value's drop method panics while it is being run as a
+/// finalizer.
+///
+/// Finalizers run on a collector-managed thread, so there is no caller for the
+/// panic to unwind into. The policy can be changed at any time with
+/// [`set_finalizer_panic_policy`].
+#[unstable(feature = "gc", issue = "none")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FinalizerPanicPolicy {
+    /// Report the type whose finalizer panicked and abort the process. This is
+    /// the default.
+    Abort,
+    /// Report the type whose finalizer panicked, then carry on running other
+    /// finalizers. The object that panicked is still freed, but any of its
+    /// fields that had not yet been dropped are leaked.
+    Log,
+    /// Pass the panic to the hook installed with
+    /// [`set_finalizer_panic_hook`], then carry on running other finalizers.
+    Hook,
+}
+
+/// Information about a panic which occurred while running a finalizer. This is
+/// passed to the hook installed with [`set_finalizer_panic_hook`].
+#[unstable(feature = "gc", issue = "none")]
+#[derive(Debug)]
+pub struct FinalizerPanicInfo<'a> {
+    type_name: &'static str,
+    payload: &'a (dyn Any + Send),
+}
+
+impl<'a> FinalizerPanicInfo<'a> {
+    /// The name of the type whose finalizer panicked, as given by
+    /// [`core::any::type_name`].
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The payload associated with the panic. This is commonly, but not
+    /// always, a `&'static str` or `String`.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn payload(&self) -> &'a (dyn Any + Send) {
+        self.payload
+    }
+
+    fn message(&self) -> &str {
+        if let Some(s) = self.payload.downcast_ref::<&'static str>() {
+            s
+        } else if let Some(s) = self.payload.downcast_ref::<String>() {
+            s.as_str()
+        } else {
+            "Box<dyn Any>"
+        }
+    }
+}
+
+type FinalizerPanicHook = Box<dyn Fn(&FinalizerPanicInfo<'_>) + Send + Sync + 'static>;
+type SharedFinalizerPanicHook = Arc<dyn Fn(&FinalizerPanicInfo<'_>) + Send + Sync + 'static>;
+
+static FINALIZER_PANIC_POLICY: AtomicU8 = AtomicU8::new(FinalizerPanicPolicy::Abort as u8);
+/// The most recently set policy other than `Hook`, which is restored when the
+/// hook is taken.
+static FINALIZER_PANIC_FALLBACK_POLICY: AtomicU8 = AtomicU8::new(FinalizerPanicPolicy::Abort as u8);
+static FINALIZER_PANIC_HOOK: RwLock<Option<SharedFinalizerPanicHook>> = RwLock::new(None);
+
+/// Sets what happens when a finalizer panics.
+///
+/// Setting the policy to [`FinalizerPanicPolicy::Hook`] without a hook
+/// installed behaves like [`FinalizerPanicPolicy::Log`].
+#[unstable(feature = "gc", issue = "none")]
+pub fn set_finalizer_panic_policy(policy: FinalizerPanicPolicy) {
+    if policy != FinalizerPanicPolicy::Hook {
+        FINALIZER_PANIC_FALLBACK_POLICY.store(policy as u8, atomic::Ordering::Relaxed);
+    }
+    FINALIZER_PANIC_POLICY.store(policy as u8, atomic::Ordering::Relaxed);
+}
+
+/// Returns the current finalizer panic policy.
+#[unstable(feature = "gc", issue = "none")]
+pub fn finalizer_panic_policy() -> FinalizerPanicPolicy {
+    match FINALIZER_PANIC_POLICY.load(atomic::Ordering::Relaxed) {
+        x if x == FinalizerPanicPolicy::Log as u8 => FinalizerPanicPolicy::Log,
+        x if x == FinalizerPanicPolicy::Hook as u8 => FinalizerPanicPolicy::Hook,
+        _ => FinalizerPanicPolicy::Abort,
+    }
+}
+
+/// Registers a custom finalizer panic hook, replacing any that was previously
+/// registered, and sets the policy to [`FinalizerPanicPolicy::Hook`].
+///
+/// The hook is called on the finalizer thread after the panic has been caught.
+/// It is given the name of the type being finalized and the panic payload.
+/// The hook may itself replace or take the current hook.
+///
+/// # Examples
+///
+/// ```
+/// # #![feature(gc)]
+/// use std::gc;
+///
+/// gc::set_finalizer_panic_hook(Box::new(|info| {
+///     eprintln!("finalizer for {} panicked", info.type_name());
+/// }));
+/// ```
+#[unstable(feature = "gc", issue = "none")]
+pub fn set_finalizer_panic_hook(hook: FinalizerPanicHook) {
+    *FINALIZER_PANIC_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::from(hook));
+    set_finalizer_panic_policy(FinalizerPanicPolicy::Hook);
+}
+
+/// Unregisters the current finalizer panic hook and returns it. If the policy
+/// is [`FinalizerPanicPolicy::Hook`], it is reset to whichever policy was in
+/// use before the hook was set ([`FinalizerPanicPolicy::Abort`] by default).
+///
+/// The hook is returned in an `Arc`, since a finalizer thread may still be
+/// running it.
+#[unstable(feature = "gc", issue = "none")]
+pub fn take_finalizer_panic_hook() -> Option<SharedFinalizerPanicHook> {
+    let hook = FINALIZER_PANIC_HOOK.write().unwrap_or_else(|e| e.into_inner()).take();
+    let fallback = FINALIZER_PANIC_FALLBACK_POLICY.load(atomic::Ordering::Relaxed);
+    let _ = FINALIZER_PANIC_POLICY.compare_exchange(
+        FinalizerPanicPolicy::Hook as u8,
+        fallback,
+        atomic::Ordering::Relaxed,
+        atomic::Ordering::Relaxed,
+    );
+    hook
+}
+
+/// Runs `f`, the body of the finalizer for the value of type `T` at `obj`, on
+/// the finalizer thread.
+///
+/// Finalizers are called by the collector through an `extern "C"` shim, so a
+/// panic must never be allowed to unwind out of here.
+#[inline]
+fn run_finalizer<T: ?Sized>(obj: *mut u8, f: impl FnOnce()) {
+    run_finalizer_body::<T>(obj, f, profiling_enabled())
+}
+
+/// Runs a cleanup action registered on `obj` with [`Gc::on_collect`].
+///
+/// These are not profiled: their type is erased, and they are not counted when
+/// they are registered, so they would skew the report.
+fn run_cleanup_action(obj: *mut u8, action: CleanupAction) {
+    run_finalizer_body::<dyn FnOnce() + Send>(obj, action, false)
+}
+
+#[inline]
+fn run_finalizer_body<T: ?Sized>(obj: *mut u8, f: impl FnOnce(), profile: bool) {
+    let _watch = watchdog_enabled().then(|| WatchdogGuard::new(core::any::type_name::<T>()));
+    let result = if profile {
+        let start = Instant::now();
+        let latency = take_enqueue_time(obj).map(|t| start.saturating_duration_since(t));
+        let result = catch_unwind(AssertUnwindSafe(f));
+        let drop_time = start.elapsed();
+        with_type_profile(core::any::type_name::<T>(), |p| {
+            p.completed += 1;
+            p.total_drop_time += drop_time;
+            p.max_drop_time = p.max_drop_time.max(drop_time);
+            if let Some(latency) = latency {
+                p.total_queue_latency += latency;
+                p.max_queue_latency = p.max_queue_latency.max(latency);
+            }
+        });
+        result
+    } else {
+        catch_unwind(AssertUnwindSafe(f))
+    };
+    let Err(payload) = result else {
+        return;
+    };
+    let info = FinalizerPanicInfo { type_name: core::any::type_name::<T>(), payload: &*payload };
+
+    let policy = finalizer_panic_policy();
+    if policy == FinalizerPanicPolicy::Hook {
+        // Clone the hook out of the lock, so that the hook can replace itself.
+        let hook = FINALIZER_PANIC_HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some(hook) = hook {
+            // The hook itself is user code, so it could panic too.
+            if catch_unwind(AssertUnwindSafe(|| hook(&info))).is_err() {
+                rtabort!("finalizer panic hook panicked");
+            }
+            return;
+        }
+    }
+
+    rtprintpanic!("finalizer for `{}` panicked: {}\n", info.type_name, info.message());
+    if policy == FinalizerPanicPolicy::Abort {
+        crate::process::abort();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Finalizer profiling
+////////////////////////////////////////////////////////////////////////////////
+
+/// Finalizer statistics for a single type, as returned by
+/// [`finalizer_profile`].
+///
+/// Only finalizers registered and run while profiling was enabled are
+/// counted.
+#[unstable(feature = "gc", issue = "none")]
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct FinalizerProfile {
+    /// The name of the finalized type, as given by [`core::any::type_name`].
+    pub type_name: &'static str,
+    /// The number of objects of this type which had a finalizer registered.
+    pub registered: u64,
+    /// The number of finalizers for this type which have finished running.
+    pub completed: u64,
+    /// The total time spent running this type's drop glue in finalizers.
+    pub total_drop_time: Duration,
+    /// The longest time spent running a single finalizer for this type.
+    pub max_drop_time: Duration,
+    /// The total time finalizers for this type spent waiting to run, measured
+    /// from when the collector queued them.
+    ///
+    /// Finalizers queued while profiling was disabled are not included. This is
+    /// also the case for a few finalizers if the collector queues a very large
+    /// number at once.
+    pub total_queue_latency: Duration,
+    /// The longest time a single finalizer for this type spent waiting to run.
+    pub max_queue_latency: Duration,
+}
+
+impl FinalizerProfile {
+    fn merge(&mut self, other: &FinalizerProfile) {
+        self.registered += other.registered;
+        self.completed += other.completed;
+        self.total_drop_time += other.total_drop_time;
+        self.max_drop_time = self.max_drop_time.max(other.max_drop_time);
+        self.total_queue_latency += other.total_queue_latency;
+        self.max_queue_latency = self.max_queue_latency.max(other.max_queue_latency);
+    }
+}
+
+static PROFILING_ENABLED: AtomicBool = AtomicBool::new(false);
+static PRINT_PROFILE_AT_EXIT: AtomicBool = AtomicBool::new(false);
+static PROFILE_EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// The finalizer statistics recorded on a single thread. Each thread only
+/// records into its own, so that threads don't contend on a lock; they are
+/// merged when the profile is read.
+struct ThreadProfile(Mutex<Option<HashMap<&'static str, FinalizerProfile>>>);
+
+impl ThreadProfile {
+    const fn new() -> ThreadProfile {
+        ThreadProfile(Mutex::new(None))
+    }
+
+    fn record(&self, type_name: &'static str, f: impl FnOnce(&mut FinalizerProfile)) {
+        let mut profiles = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let profile = profiles
+            .get_or_insert_with(HashMap::new)
+            .entry(type_name)
+            .or_insert_with(|| FinalizerProfile { type_name, ..Default::default() });
+        f(profile);
+    }
+}
+
+/// The profile of every thread which has recorded statistics. These are kept
+/// after the thread exits, so that its statistics are not lost.
+static THREAD_PROFILES: Mutex<Vec<Arc<ThreadProfile>>> = Mutex::new(Vec::new());
+/// Used instead of the thread's own profile once its thread locals have been
+/// destroyed.
+static EXITING_THREAD_PROFILE: ThreadProfile = ThreadProfile::new();
+
+thread_local! {
+    static THREAD_PROFILE: Arc<ThreadProfile> = {
+        let profile = Arc::new(ThreadProfile::new());
+        THREAD_PROFILES.lock().unwrap_or_else(|e| e.into_inner()).push(Arc::clone(&profile));
+        profile
+    };
+}
+
+#[inline(always)]
+fn profiling_enabled() -> bool {
+    PROFILING_ENABLED.load(atomic::Ordering::Relaxed)
+}
+
+fn with_type_profile(type_name: &'static str, f: impl FnOnce(&mut FinalizerProfile)) {
+    let mut f = Some(f);
+    let _ = THREAD_PROFILE.try_with(|profile| profile.record(type_name, f.take().unwrap()));
+    if let Some(f) = f {
+        EXITING_THREAD_PROFILE.record(type_name, f);
+    }
+}
+
+/// Calls `f` with the statistics recorded by each thread.
+fn for_each_thread_profile(mut f: impl FnMut(&mut HashMap<&'static str, FinalizerProfile>)) {
+    let threads = THREAD_PROFILES.lock().unwrap_or_else(|e| e.into_inner());
+    for profile in threads.iter().map(|p| &**p).chain([&EXITING_THREAD_PROFILE]) {
+        if let Some(profiles) = profile.0.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            f(profiles);
+        }
+    }
+}
+
+/// An object whose finalizer has been queued by the collector, and when.
+struct EnqueuedFinalizer {
+    /// The address of the object with all of its bits flipped, or zero if the
+    /// slot is free. The address is disguised so that this table, which the
+    /// collector scans as a root, does not keep the object alive.
+    obj: AtomicUsize,
+    /// When the finalizer was queued, in nanoseconds since `PROFILE_EPOCH`.
+    at: AtomicU64,
+}
+
+const ENQUEUED_SLOTS: usize = 4096;
+/// How many slots after an object's preferred slot are searched.
+const ENQUEUED_PROBES: usize = 16;
+const NO_ENQUEUED_FINALIZER: EnqueuedFinalizer =
+    EnqueuedFinalizer { obj: AtomicUsize::new(0), at: AtomicU64::new(0) };
+/// The queued finalizers which have not run yet. This is a fixed-size table,
+/// because it is written to by the collector while it holds the allocation
+/// lock, so it can't allocate.
+static ENQUEUED_FINALIZERS: [EnqueuedFinalizer; ENQUEUED_SLOTS] =
+    [NO_ENQUEUED_FINALIZER; ENQUEUED_SLOTS];
+
+fn enqueued_slots(obj: *mut u8) -> impl Iterator<Item = &'static EnqueuedFinalizer> {
+    let start = obj.addr() / MIN_ALIGN;
+    (0..ENQUEUED_PROBES).map(move |i| &ENQUEUED_FINALIZERS[(start + i) % ENQUEUED_SLOTS])
+}
+
+/// Called by the collector for each object whose finalizer it queues. This
+/// runs with the allocation lock held, so it must not allocate or block. As a
+/// result, this is the only place which fills slots in the table.
+extern "C" fn on_finalizer_enqueued(obj: *mut u8) {
+    let Some(epoch) = PROFILE_EPOCH.get() else {
+        return;
+    };
+    let at = epoch.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+    // If every slot is taken, the latency of this finalizer is not recorded.
+    if let Some(slot) = enqueued_slots(obj).find(|s| s.obj.load(atomic::Ordering::Relaxed) == 0) {
+        slot.at.store(at, atomic::Ordering::Relaxed);
+        slot.obj.store(hide_pointer(obj), atomic::Ordering::Release);
+    }
+}
+
+/// Returns when the finalizer for `obj` was queued, if it was recorded, and
+/// frees its slot.
+fn take_enqueue_time(obj: *mut u8) -> Option<Instant> {
+    let epoch = PROFILE_EPOCH.get()?;
+    let slot =
+        enqueued_slots(obj).find(|s| s.obj.load(atomic::Ordering::Acquire) == hide_pointer(obj))?;
+    let at = slot.at.load(atomic::Ordering::Relaxed);
+    slot.obj.store(0, atomic::Ordering::Release);
+    Some(*epoch + Duration::from_nanos(at))
+}
+
+/// Starts recording per-type finalizer statistics.
+///
+/// While profiling is disabled (the default), the only overhead is a single
+/// relaxed atomic load when a finalizer is registered or run. Profiling can
+/// also be enabled for a whole program run by setting the `RUST_GC_PROFILE`
+/// environment variable, in which case the profile is printed to stderr when
+/// `main` returns.
+#[unstable(feature = "gc", issue = "none")]
+pub fn enable_finalizer_profiling() {
+    PROFILE_EPOCH.get_or_init(Instant::now);
+    unsafe { bdwgc::GC_set_await_finalize_proc(Some(on_finalizer_enqueued)) };
+    PROFILING_ENABLED.store(true, atomic::Ordering::Relaxed);
+}
+
+/// Stops recording finalizer statistics. Previously recorded statistics are
+/// kept.
+#[unstable(feature = "gc", issue = "none")]
+pub fn disable_finalizer_profiling() {
+    PROFILING_ENABLED.store(false, atomic::Ordering::Relaxed);
+    unsafe { bdwgc::GC_set_await_finalize_proc(None) };
+    // Finalizers queued before now must not count towards the latencies
+    // recorded if profiling is enabled again.
+    for slot in &ENQUEUED_FINALIZERS {
+        slot.obj.store(0, atomic::Ordering::Release);
+    }
+}
+
+/// Clears all recorded finalizer statistics.
+#[unstable(feature = "gc", issue = "none")]
+pub fn reset_finalizer_profile() {
+    for_each_thread_profile(|profiles| profiles.clear());
+}
+
+/// Returns the finalizer statistics recorded so far, one entry per type,
+/// sorted by the total time spent in drop (longest first).
+#[unstable(feature = "gc", issue = "none")]
+pub fn finalizer_profile() -> Vec<FinalizerProfile> {
+    let mut merged: HashMap<&'static str, FinalizerProfile> = HashMap::new();
+    for_each_thread_profile(|profiles| {
+        for p in profiles.values() {
+            merged
+                .entry(p.type_name)
+                .or_insert_with(|| FinalizerProfile {
+                    type_name: p.type_name,
+                    ..Default::default()
+                })
+                .merge(p);
+        }
+    });
+    let mut profiles: Vec<_> = merged.into_values().collect();
+    profiles.sort_by(|a, b| {
+        b.total_drop_time.cmp(&a.total_drop_time).then(a.type_name.cmp(b.type_name))
+    });
+    profiles
+}
+
+/// Prints the finalizer statistics recorded so far to stderr.
+#[unstable(feature = "gc", issue = "none")]
+pub fn print_finalizer_profile() {
+    let profiles = finalizer_profile();
+    let backlog: u64 = profiles.iter().map(|p| p.registered.saturating_sub(p.completed)).sum();
+    eprintln!("finalizer profile ({} types, {backlog} pending):", profiles.len());
+    eprintln!(
+        "{:>12} {:>12} {:>14} {:>14} {:>14}  type",
+        "registered", "completed", "drop total", "drop max", "latency max"
+    );
+    for p in profiles {
+        eprintln!(
+            "{:>12} {:>12} {:>14?} {:>14?} {:>14?}  {}",
+            p.registered,
+            p.completed,
+            p.total_drop_time,
+            p.max_drop_time,
+            p.max_queue_latency,
+            p.type_name
+        );
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Finalizer threads
+////////////////////////////////////////////////////////////////////////////////
+
+/// Configures and starts dedicated threads for running finalizers.
+///
+/// By default, finalizers are run by the collector on threads which cannot be
+/// named, sized or monitored. Calling [`spawn`] switches the collector to
+/// queueing finalizers, which are then run by a pool of ordinary std threads
+/// with the given configuration.
+///
+/// Optionally, a watchdog thread can report any finalizer which runs for
+/// longer than a given threshold, since one slow drop method stalls every
+/// other finalizer behind it on the same thread.
+///
+/// Finalizer threads can only be configured once per process.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #![feature(gc)]
+/// use std::gc::FinalizerThreadBuilder;
+/// use std::time::Duration;
+///
+/// FinalizerThreadBuilder::new()
+///     .count(2)
+///     .name("finalizer".to_string())
+///     .stack_size(256 * 1024)
+///     .stall_threshold(Duration::from_millis(100))
+///     .spawn()
+///     .unwrap();
+/// ```
+///
+/// [`spawn`]: FinalizerThreadBuilder::spawn
+#[unstable(feature = "gc", issue = "none")]
+#[derive(Debug)]
+#[must_use = "must eventually spawn the finalizer threads"]
+pub struct FinalizerThreadBuilder {
+    count: usize,
+    name: Option<String>,
+    stack_size: Option<usize>,
+    stall_threshold: Option<Duration>,
+}
+
+impl FinalizerThreadBuilder {
+    /// Creates a builder for a single, unnamed finalizer thread with the
+    /// default stack size and no stall detection.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn new() -> FinalizerThreadBuilder {
+        FinalizerThreadBuilder { count: 1, name: None, stack_size: None, stall_threshold: None }
+    }
+
+    /// Sets the number of finalizer threads. This must be at least one.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn count(mut self, count: usize) -> FinalizerThreadBuilder {
+        self.count = count;
+        self
+    }
+
+    /// Sets the name prefix for finalizer threads. Each thread is named
+    /// `{name}-{n}`. The default prefix is `gc-finalizer`.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn name(mut self, name: String) -> FinalizerThreadBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    /// Sets the stack size in bytes for each finalizer thread.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn stack_size(mut self, size: usize) -> FinalizerThreadBuilder {
+        self.stack_size = Some(size);
+        self
+    }
+
+    /// Reports any finalizer which has been running for longer than
+    /// `threshold`, along with the type being finalized. The threshold must be
+    /// non-zero.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn stall_threshold(mut self, threshold: Duration) -> FinalizerThreadBuilder {
+        self.stall_threshold = Some(threshold);
+        self
+    }
+
+    /// Spawns the finalizer threads, and the watchdog thread if a stall
+    /// threshold was set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if finalizer threads have already been spawned, if the
+    /// count or stall threshold is zero, or if the OS fails to create a thread.
+    /// In the last case, any threads which were created exit, and `spawn` can
+    /// be called again.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn spawn(self) -> io::Result<()> {
+        if self.count == 0 {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "at least one finalizer thread is required",
+            ));
+        }
+        if self.stall_threshold.is_some_and(|t| t.is_zero()) {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "the stall threshold must be non-zero",
+            ));
+        }
+        if FINALIZER_THREADS_SPAWNED.swap(true, atomic::Ordering::AcqRel) {
+            return Err(io::const_io_error!(
+                io::ErrorKind::AlreadyExists,
+                "finalizer threads have already been spawned",
+            ));
+        }
+        self.spawn_threads().inspect_err(|_| {
+            // Tell any threads which were created to exit, then allow another
+            // attempt.
+            FINALIZER_THREADS_GENERATION.fetch_add(1, atomic::Ordering::Relaxed);
+            drop(FINALIZERS_PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+            FINALIZERS_PENDING_CVAR.notify_all();
+            FINALIZER_THREADS_SPAWNED.store(false, atomic::Ordering::Release);
+        })?;
+
+        // From now on, the collector queues finalizers and calls
+        // `notify_finalizers` instead of running them itself.
+        unsafe {
+            bdwgc::GC_set_finalizer_notifier(Some(notify_finalizers));
+            bdwgc::GC_set_finalize_on_demand(1);
+        }
+        Ok(())
+    }
+
+    fn spawn_threads(self) -> io::Result<()> {
+        let generation = FINALIZER_THREADS_GENERATION.load(atomic::Ordering::Relaxed);
+        let prefix = self.name.unwrap_or_else(|| "gc-finalizer".to_string());
+        for n in 0..self.count {
+            let mut builder = thread::Builder::new().name(format!("{prefix}-{n}"));
+            if let Some(size) = self.stack_size {
+                builder = builder.stack_size(size);
+            }
+            builder.spawn(move || finalizer_thread_main(generation))?;
+        }
+
+        if let Some(threshold) = self.stall_threshold {
+            thread::Builder::new()
+                .name(format!("{prefix}-watchdog"))
+                .spawn(move || watchdog_thread_main(threshold))?;
+            WATCHDOG_THRESHOLD_NANOS.store(
+                threshold.as_nanos().try_into().unwrap_or(u64::MAX),
+                atomic::Ordering::Relaxed,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl Default for FinalizerThreadBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static FINALIZER_THREADS_SPAWNED: AtomicBool = AtomicBool::new(false);
+/// Bumped when spawning finalizer threads fails, which tells the threads that
+/// were created before the failure to exit.
+static FINALIZER_THREADS_GENERATION: AtomicUsize = AtomicUsize::new(0);
+/// Set when the collector has queued finalizers, and cleared by the finalizer
+/// thread which goes on to run them.
+static FINALIZERS_PENDING: Mutex<bool> = Mutex::new(false);
+static FINALIZERS_PENDING_CVAR: Condvar = Condvar::new();
+
+extern "C" fn notify_finalizers() {
+    *FINALIZERS_PENDING.lock().unwrap_or_else(|e| e.into_inner()) = true;
+    FINALIZERS_PENDING_CVAR.notify_all();
+}
+
+fn finalizer_thread_main(generation: usize) {
+    loop {
+        {
+            let mut pending = FINALIZERS_PENDING.lock().unwrap_or_else(|e| e.into_inner());
+            while !*pending {
+                if FINALIZER_THREADS_GENERATION.load(atomic::Ordering::Relaxed) != generation {
+                    return;
+                }
+                pending = FINALIZERS_PENDING_CVAR.wait(pending).unwrap_or_else(|e| e.into_inner());
+            }
+            *pending = false;
+        }
+        // Other finalizer threads may race us for queued finalizers. That's
+        // fine: each finalizer is dequeued and run by exactly one thread.
+        unsafe { bdwgc::GC_invoke_finalizers() };
+    }
+}
+
+/// The stall threshold in nanoseconds, or zero if the watchdog is disabled.
+static WATCHDOG_THRESHOLD_NANOS: AtomicU64 = AtomicU64::new(0);
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(0);
+/// The finalizers which are currently running, keyed by a unique id.
+static RUNNING_FINALIZERS: Mutex<Option<HashMap<u64, RunningFinalizer>>> = Mutex::new(None);
+
+struct RunningFinalizer {
+    type_name: &'static str,
+    thread: thread::Thread,
+    start: Instant,
+    reported: bool,
+}
+
+#[inline(always)]
+fn watchdog_enabled() -> bool {
+    WATCHDOG_THRESHOLD_NANOS.load(atomic::Ordering::Relaxed) != 0
+}
+
+/// Tracks a running finalizer for the watchdog until it is dropped. This is
+/// only created while the watchdog is enabled, so that finalizers don't take
+/// its lock otherwise.
+struct WatchdogGuard(u64);
+
+impl WatchdogGuard {
+    fn new(type_name: &'static str) -> WatchdogGuard {
+        let id = NEXT_WATCH_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        let running = RunningFinalizer {
+            type_name,
+            thread: thread::current(),
+            start: Instant::now(),
+            reported: false,
+        };
+        RUNNING_FINALIZERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(HashMap::new)
+            .insert(id, running);
+        WatchdogGuard(id)
+    }
+}
+
+impl Drop for WatchdogGuard {
+    fn drop(&mut self) {
+        let mut running = RUNNING_FINALIZERS.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(r) = running.as_mut().and_then(|r| r.remove(&self.0)) else {
+            return;
+        };
+        if r.reported {
+            rtprintpanic!(
+                "finalizer for `{}` on thread '{}' finished after {:?}\n",
+                r.type_name,
+                r.thread.name().unwrap_or("<unnamed>"),
+                r.start.elapsed()
+            );
+        }
+    }
+}
+
+fn watchdog_thread_main(threshold: Duration) {
+    let interval = (threshold / 2).max(Duration::from_millis(1));
+    loop {
+        thread::sleep(interval);
+        let mut running = RUNNING_FINALIZERS.lock().unwrap_or_else(|e| e.into_inner());
+        for r in running.iter_mut().flat_map(|r| r.values_mut()) {
+            if !r.reported && r.start.elapsed() > threshold {
+                r.reported = true;
+                rtprintpanic!(
+                    "finalizer for `{}` on thread '{}' has been running for over {:?}\n",
+                    r.type_name,
+                    r.thread.name().unwrap_or("<unnamed>"),
+                    threshold
+                );
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Thread-local finalization
+////////////////////////////////////////////////////////////////////////////////
+
+/// A finalizer which has been queued to run on its allocating thread.
+///
+/// These are allocated as uncollectable GC objects, so the collector treats
+/// them as roots. This keeps the object (and everything reachable from it)
+/// alive until its finalizer has actually run.
+struct LocalFinalizer {
+    obj: *mut u8,
+    finalize: fn(*mut u8),
+    /// Cleanup actions registered on the object, which run after `finalize`.
+    actions: Vec<(u64, CleanupAction)>,
+}
+
+struct LocalFinalizerState {
+    /// Set once the owning thread has exited, after which nothing will ever
+    /// run newly queued finalizers.
+    closed: bool,
+    pending: Vec<*mut LocalFinalizer>,
+}
+
+struct LocalFinalizerQueue(Mutex<LocalFinalizerState>);
+
+// SAFETY: the raw pointers in the queue are only dereferenced on the owning
+// thread, in `LocalFinalizerQueue::run`.
+unsafe impl Send for LocalFinalizerQueue {}
+unsafe impl Sync for LocalFinalizerQueue {}
+
+impl LocalFinalizerQueue {
+    /// Called from the collector's finalizer for `obj`.
+    fn push(&self, obj: *mut u8, finalize: fn(*mut u8)) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if state.closed {
+            // The object is leaked, as if it were never finalizable.
+            return;
+        }
+        let actions = take_cleanups(obj).map(|c| c.actions).unwrap_or_default();
+        unsafe {
+            let node = bdwgc::GC_malloc_uncollectable(mem::size_of::<LocalFinalizer>())
+                as *mut LocalFinalizer;
+            if node.is_null() {
+                handle_alloc_error(Layout::new::<LocalFinalizer>());
+            }
+            node.write(LocalFinalizer { obj, finalize, actions });
+            state.pending.push(node);
+        }
+    }
+
+    /// Runs every queued finalizer. This must only be called on the owning
+    /// thread.
+    fn run(&self, close: bool) -> usize {
+        let mut count = 0;
+        loop {
+            let pending = {
+                let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+                state.closed |= close;
+                mem::take(&mut state.pending)
+            };
+            if pending.is_empty() {
+                return count;
+            }
+            // Finalizers may make other local objects unreachable, so keep
+            // going until the queue is empty.
+            for node in pending {
+                let LocalFinalizer { obj, finalize, actions } = unsafe { node.read() };
+                unsafe { bdwgc::GC_free(node as *mut u8) };
+                finalize(obj);
+                for (_, action) in actions {
+                    run_cleanup_action(obj, action);
+                }
+                count += 1;
+            }
+        }
+    }
+}
+
+struct LocalFinalizerHandle(Arc<LocalFinalizerQueue>);
+
+impl Drop for LocalFinalizerHandle {
+    fn drop(&mut self) {
+        // Thread exit is the last safe point at which this thread's queued
+        // finalizers can run.
+        self.0.run(true);
+    }
+}
+
+thread_local! {
+    static LOCAL_FINALIZERS: LocalFinalizerHandle = LocalFinalizerHandle(Arc::new(
+        LocalFinalizerQueue(Mutex::new(LocalFinalizerState { closed: false, pending: Vec::new() }))
+    ));
+}
+
+/// Runs the finalizers which have been queued for values allocated on this
+/// thread with [`Gc::new_local`], returning how many were run.
+///
+/// Finalizers queued while this function is running (for example, because a
+/// finalizer dropped the last reference to another local value) are also run
+/// before it returns.
+#[unstable(feature = "gc", issue = "none")]
+pub fn run_local_finalizers() -> usize {
+    LOCAL_FINALIZERS.try_with(|q| q.0.run(false)).unwrap_or(0)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Cleanup actions
+////////////////////////////////////////////////////////////////////////////////
+
+type CleanupAction = Box<dyn FnOnce() + Send + 'static>;
+
+/// The cleanup actions registered for a single object.
+struct Cleanups {
+    /// The finalizer that was registered for the object before its first
+    /// cleanup action, which must still be run.
+    old_finalizer: Option<unsafe extern "C" fn(*mut u8, *mut u8)>,
+    old_client_data: *mut u8,
+    actions: Vec<(u64, CleanupAction)>,
+}
+
+// SAFETY: `old_client_data` is opaque to us, and is only passed back to
+// `old_finalizer` on the finalizer thread, as the collector would have done.
+unsafe impl Send for Cleanups {}
+
+static NEXT_CLEANUP_ID: AtomicU64 = AtomicU64::new(0);
+/// Cleanup actions keyed by the disguised base address of the object they are
+/// registered on.
+static CLEANUPS: Mutex<Option<HashMap<usize, Cleanups>>> = Mutex::new(None);
+
+/// Returns the address of `obj` with all of its bits flipped, in the style of
+/// `GC_HIDE_POINTER`. Memory which the collector scans can hold this without
+/// keeping `obj` alive.
+#[inline]
+fn hide_pointer(obj: *mut u8) -> usize {
+    !obj.addr()
+}
+
+/// Removes the cleanup actions registered on `obj`.
+fn take_cleanups(obj: *mut u8) -> Option<Cleanups> {
+    CLEANUPS.lock().unwrap_or_else(|e| e.into_inner()).as_mut()?.remove(&hide_pointer(obj))
+}
+
+unsafe extern "C" fn cleanup_shim(obj: *mut u8, _: *mut u8) {
+    let old = CLEANUPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|c| c.get(&hide_pointer(obj)))
+        .map(|c| (c.old_finalizer, c.old_client_data));
+    let Some((old_finalizer, old_client_data)) = old else {
+        return;
+    };
+    if let Some(finalizer) = old_finalizer {
+        // If the object was allocated with `Gc::new_local`, this only queues
+        // its finalizer, and takes the actions so that they run after it.
+        unsafe { finalizer(obj, old_client_data) };
+    } else {
+        // The object has no finalizer of its own, so nothing else frees its
+        // slot in the profiler's table of queued finalizers.
+        let _ = take_enqueue_time(obj);
+    }
+    let Some(Cleanups { actions, .. }) = take_cleanups(obj) else {
+        return;
+    };
+    for (_, action) in actions {
+        run_cleanup_action(obj, action);
+    }
+}
+
+/// A handle to a cleanup action registered with [`Gc::on_collect`].
+#[unstable(feature = "gc", issue = "none")]
+#[derive(Debug)]
+#[must_use = "dropping a `Cleanup` does not cancel it; use `let _ = ...` to ignore it"]
+pub struct Cleanup {
+    /// The base address of the object, disguised with [`hide_pointer`] so that
+    /// the handle does not keep the object alive.
+    base: usize,
+    id: u64,
+}
+
+impl Cleanup {
+    fn take(&self) -> Option<CleanupAction> {
+        let mut cleanups = CLEANUPS.lock().unwrap_or_else(|e| e.into_inner());
+        let actions = &mut cleanups.as_mut()?.get_mut(&self.base)?.actions;
+        let idx = actions.iter().position(|(id, _)| *id == self.id)?;
+        Some(actions.remove(idx).1)
+    }
+
+    /// Cancels the cleanup action so that it never runs. Returns `false` if
+    /// the action has already run (or started running).
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn cancel(self) -> bool {
+        self.take().is_some()
+    }
+
+    /// Runs the cleanup action now, on the current thread, instead of when the
+    /// object is collected. Returns `false` if the action has already run (or
+    /// started running).
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn clean(self) -> bool {
+        match self.take() {
+            Some(action) => {
+                action();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // GC API
 ////////////////////////////////////////////////////////////////////////////////
 
+#[repr(transparent)]
 struct GcBox<T: ?Sized> {
     /// The object being garbage collected.
     value: T,
 }
 
+/// Selects what the collector runs when a `GcBox<T>` is finalized: either
+/// `T::finalize` if `T` implements [`Finalize`], or `T`'s drop glue. In the
+/// first case, the fields of `T` are not dropped afterwards.
+///
+/// The compiler must make the same choice when deciding which body finalizer
+/// safety analysis checks, so `Ty::is_finalize` covers every type this does.
+trait FinalizeGlue {
+    unsafe fn finalize_in_place(this: *mut Self);
+}
+
+impl<T: ?Sized> FinalizeGlue for GcBox<T> {
+    default unsafe fn finalize_in_place(this: *mut Self) {
+        unsafe { drop_in_place(this) }
+    }
+}
+
+impl<T: ?Sized + Finalize> FinalizeGlue for GcBox<T> {
+    unsafe fn finalize_in_place(this: *mut Self) {
+        unsafe { (*this).value.finalize() }
+    }
+}
+
 /// A multi-threaded garbage collected pointer.
 ///
 /// See the [module-level documentation](./index.html) for more details.
@@ -231,6 +1211,16 @@ pub struct Gc<T: ?Sized> {
 unsafe impl<T: ?Sized + Send> Send for Gc<T> {}
 unsafe impl<T: ?Sized + Sync + Send> Sync for Gc<T> {}
 
+#[unstable(feature = "gc", issue = "none")]
+impl<T: RefUnwindSafe + ?Sized> UnwindSafe for Gc<T> {}
+#[unstable(feature = "gc", issue = "none")]
+impl<T: RefUnwindSafe + ?Sized> RefUnwindSafe for Gc<T> {}
+
+/// The collector never moves objects, so a `Gc<T>` is always `Unpin`, even if
+/// `T` is not.
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Unpin for Gc<T> {}
+
 // In non-topological finalization, it is unsound to deref any fields of type
 // `Gc` from within a finalizer. This is because it could have been finalized
 // first, thus resulting in a dangling reference. Marking this as
@@ -297,16 +1287,10 @@ unsafe fn from_ptr(ptr: *mut GcBox<T>) -> Self {
     /// size and alignment of the originally allocated block.
     #[unstable(feature = "gc", issue = "none")]
     pub fn from_raw(raw: *const T) -> Gc<T> {
-        let layout = Layout::new::<GcBox<()>>();
-        // Align the unsized value to the end of the GcBox.
-        // Because GcBox is repr(C), it will always be the last field in memory.
-        // SAFETY: since the only unsized types for T possible are slices, trait objects,
-        // and extern types, the input safety requirement is currently enough to
-        // satisfy the requirements of align_of_val_raw.
-        let raw_align = unsafe { align_of_val_raw(raw) };
-        let offset = layout.size() + layout.padding_needed_for(raw_align);
-        // Reverse the offset to find the original GcBox.
-        let box_ptr = unsafe { raw.byte_sub(offset) as *mut GcBox<T> };
+        // `GcBox` is `repr(transparent)`, so the value is at offset 0 and a
+        // pointer to it is also a pointer to the `GcBox` (with the same
+        // metadata, if `T` is unsized).
+        let box_ptr = raw as *mut GcBox<T>;
         unsafe { Self::from_ptr(box_ptr) }
     }
 
@@ -327,6 +1311,111 @@ pub fn as_ptr(this: &Self) -> *const T {
     pub fn ptr_eq(this: &Self, other: &Self) -> bool {
         crate::ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
     }
+
+    /// Makes a [`GcProjection`] to a component of the value held by this
+    /// `Gc`, such as one of its fields.
+    ///
+    /// The projection keeps the whole allocation alive for as long as it is
+    /// reachable. No extra allocation is made and no additional finalizer is
+    /// registered: the base object is finalized exactly once, when neither the
+    /// `Gc` nor any projection derived from it are reachable.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Gc::map(...)`, so that it does not clash with a method of the same
+    /// name on `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// struct Point {
+    ///     x: u64,
+    ///     y: u64,
+    /// }
+    ///
+    /// let p = Gc::new(Point { x: 1, y: 2 });
+    /// let y = Gc::map(p, |p| &p.y);
+    /// assert_eq!(*y, 2);
+    /// ```
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> GcProjection<U, T>
+    where
+        F: for<'a> FnOnce(&'a T) -> &'a U,
+    {
+        let ptr = NonNull::from(f(&this.inner().value));
+        GcProjection { base: this, ptr }
+    }
+
+    /// Registers a cleanup action to run after the object pointed to by this
+    /// `Gc` has become unreachable.
+    ///
+    /// This is similar to `java.lang.ref.Cleaner`: unlike a [`Drop`] impl on
+    /// `T`, it applies to a single object rather than to every value of a
+    /// type, and the action is not given access to the object. Several actions
+    /// can be registered on the same object. They run after the object's own
+    /// finalizer (if it has one), in the order they were registered. This is
+    /// on the finalizer thread, or on the allocating thread if the object was
+    /// allocated with [`Gc::new_local`].
+    ///
+    /// The action must be [`FinalizerSafe`], which prevents it from capturing
+    /// a `Gc` to the object itself: if it could, the object would never be
+    /// unreachable.
+    ///
+    /// The returned [`Cleanup`] can be used to cancel the action, or to run it
+    /// early. Dropping it does nothing.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Gc::on_collect(...)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let gc = Gc::new([0u8; 64]);
+    /// let cleanup = Gc::on_collect(&gc, || println!("collected"));
+    /// assert!(cleanup.cancel());
+    /// ```
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn on_collect<F>(this: &Self, action: F) -> Cleanup
+    where
+        F: FnOnce() + Send + FinalizerSafe + 'static,
+    {
+        let base = unsafe { bdwgc::GC_base(this.ptr.as_ptr() as *mut u8) };
+        let id = NEXT_CLEANUP_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        if base.is_null() {
+            // Zero-sized values are not allocated on the GC heap, so they are
+            // never collected.
+            return Cleanup { base: hide_pointer(base), id };
+        }
+
+        let mut cleanups = CLEANUPS.lock().unwrap_or_else(|e| e.into_inner());
+        let cleanups = cleanups.get_or_insert_with(HashMap::new);
+        let entry = cleanups.entry(hide_pointer(base)).or_insert_with(|| {
+            // Take over the object's finalizer, remembering whichever one was
+            // registered before so that it can be chained.
+            let mut old_finalizer = None;
+            let mut old_client_data = ptr::null_mut();
+            unsafe {
+                bdwgc::GC_register_finalizer_no_order(
+                    base,
+                    Some(cleanup_shim),
+                    ptr::null_mut(),
+                    &mut old_finalizer,
+                    &mut old_client_data,
+                );
+            }
+            if old_finalizer.is_none() {
+                GC_COUNTERS.finalizers_registered.fetch_add(1, atomic::Ordering::Relaxed);
+            }
+            Cleanups { old_finalizer, old_client_data, actions: Vec::new() }
+        });
+        entry.actions.push((id, Box::new(action)));
+        Cleanup { base: hide_pointer(base), id }
+    }
 }
 
 impl<T> Gc<T> {
@@ -342,7 +1431,7 @@ impl<T> Gc<T> {
     /// ```
     #[cfg(not(no_global_oom_handling))]
     #[unstable(feature = "gc", issue = "none")]
-    #[cfg_attr(not(test), rustc_diagnostic_item = "gc_ctor")]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
     pub fn new(value: T) -> Self {
         unsafe { Self::new_internal(value) }
     }
@@ -381,6 +1470,7 @@ impl<T> Gc<T> {
     /// ```
     #[cfg(not(no_global_oom_handling))]
     #[unstable(feature = "gc", issue = "none")]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor(unfinalized))]
     pub fn new_unfinalizable(value: T) -> Self {
         unsafe { Self::new_internal(value) }
     }
@@ -402,13 +1492,86 @@ pub fn new_unfinalizable(value: T) -> Self {
     /// run concurrently while that shared data is accessed without
     /// synchronisation elsewhere.
     ///
+    /// Apart from `Sync`, the drop method is still checked by finalizer safety
+    /// analysis, as it would be for [`Gc::new`].
+    ///
     /// [`Sync`]: core::marker::Sync
     #[cfg(not(no_global_oom_handling))]
     #[unstable(feature = "gc", issue = "none")]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor(unsynchronised))]
     pub unsafe fn new_unsynchronised(value: T) -> Self {
         unsafe { Self::new_internal(value) }
     }
 
+    /// Constructs a new `Gc<T>` whose value is finalized on the thread which
+    /// allocated it, rather than on a separate finalizer thread.
+    ///
+    /// This means that `T` does not need to implement [`Send`] or [`Sync`] in
+    /// order to have its [drop method] run, so it can contain types such as
+    /// `Rc` or `RefCell`.
+    ///
+    /// Once the value is unreachable, its finalizer is queued on the
+    /// allocating thread. Queued finalizers are only run when that thread
+    /// calls [`run_local_finalizers`], or when the thread exits. If the thread
+    /// has already exited by the time the value becomes unreachable, its drop
+    /// method is never run (as with [`Gc::new_unfinalizable`]). The same goes
+    /// for values allocated while the thread is exiting (e.g. from the
+    /// destructor of a thread local), once its queue of local finalizers has
+    /// been destroyed.
+    ///
+    /// [`Send`]: core::marker::Send
+    /// [`Sync`]: core::marker::Sync
+    /// [drop method]: core::ops::Drop#tymethod.drop
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::{self, Gc};
+    /// use std::rc::Rc;
+    ///
+    /// let shared = Rc::new(5);
+    /// let gc = Gc::new_local(Rc::clone(&shared));
+    /// assert_eq!(**gc, 5);
+    ///
+    /// // Later, at a point where it is convenient for this thread to run
+    /// // finalizers:
+    /// gc::run_local_finalizers();
+    /// ```
+    #[cfg(not(no_global_oom_handling))]
+    #[unstable(feature = "gc", issue = "none")]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor(local))]
+    pub fn new_local(value: T) -> Self {
+        #[cfg(not(bootstrap))]
+        if !crate::mem::needs_finalizer::<T>() {
+            return unsafe { Self::new_internal(value) };
+        }
+
+        unsafe extern "C" fn local_finalizer_shim<T>(obj: *mut u8, queue: *mut u8) {
+            let queue = unsafe { Arc::from_raw(queue as *const LocalFinalizerQueue) };
+            queue.push(obj, |obj| {
+                let drop_fn = <GcBox<T> as FinalizeGlue>::finalize_in_place;
+                run_finalizer::<T>(obj, || unsafe { drop_fn(obj as *mut GcBox<T>) });
+            });
+        }
+
+        let ptr = Box::leak(Box::new_in(GcBox { value }, GcAllocator));
+        let Ok(queue) = LOCAL_FINALIZERS.try_with(|q| Arc::into_raw(Arc::clone(&q.0))) else {
+            // The thread is exiting, so nothing would ever run the finalizer.
+            // Finalizing it on another thread instead would not be sound, as
+            // `T` need not be `Send`.
+            return Self::from_inner(ptr.into());
+        };
+        unsafe {
+            register_finalizer::<T>(
+                ptr as *mut _ as *mut u8,
+                local_finalizer_shim::<T>,
+                queue as *mut u8,
+            );
+            Self::from_inner(ptr.into())
+        }
+    }
+
     #[inline(always)]
     #[cfg(not(no_global_oom_handling))]
     unsafe fn new_internal(value: T) -> Self {
@@ -418,13 +1581,14 @@ unsafe fn new_internal(value: T) -> Self {
         }
 
         unsafe extern "C" fn finalizer_shim<T>(obj: *mut u8, _: *mut u8) {
-            let drop_fn = drop_in_place::<GcBox<T>>;
-            drop_fn(obj as *mut GcBox<T>);
+            let drop_fn = <GcBox<T> as FinalizeGlue>::finalize_in_place;
+            run_finalizer::<T>(obj, || unsafe { drop_fn(obj as *mut GcBox<T>) });
         }
 
         // By explicitly using type parameters here, we force rustc to compile monomorphised drop
         // glue for `GcBox<T>`. This ensures that the fn pointer points to the correct drop method
-        // (or chain of drop methods) for the type `T`.
+        // (or chain of drop methods) for the type `T`. If `T` implements `Finalize`, the fn
+        // pointer instead points to a call to `T::finalize`.
         //
         // Note that even though `GcBox` has no drop implementation, we still reify a
         // `drop_in_place` for `GcBox<T>`, and not`T`. This is because `T` may have an alignment
@@ -435,38 +1599,262 @@ unsafe fn new_internal(value: T) -> Self {
         // the finaliser.
         let ptr = Box::leak(Box::new_in(GcBox { value }, GcAllocator));
         unsafe {
-            bdwgc::GC_register_finalizer_no_order(
+            register_finalizer::<T>(
                 ptr as *mut _ as *mut u8,
-                Some(finalizer_shim::<T>),
-                ptr::null_mut(),
-                ptr::null_mut(),
+                finalizer_shim::<T>,
                 ptr::null_mut(),
             );
         }
-        GC_COUNTERS.finalizers_registered.fetch_add(1, atomic::Ordering::Relaxed);
         Self::from_inner(ptr.into())
     }
 }
 
-#[cfg(profile_gc)]
+impl<T: ?Sized> Gc<T> {
+    /// Converts a `Box<T, GcAllocator>` into a `Gc<T>` without copying the
+    /// value.
+    ///
+    /// The box's existing allocation is reused for the `Gc`, and a finalizer
+    /// is registered for it afterwards if `T` needs finalizing. `T` may be
+    /// unsized, so this can be used to turn a `Box<dyn Trait, GcAllocator>`
+    /// into a `Gc<dyn Trait>`. Since the concrete type behind a trait object
+    /// is not known, trait objects always get a finalizer.
+    ///
+    /// As with [`Gc::new`], finalizer safety analysis checks that `T` can be
+    /// safely finalized. For a trait object, this checks the concrete type
+    /// which was unsized into it, if that happened in the same function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// # #![feature(allocator_api)]
+    /// use std::gc::{Gc, GcAllocator};
+    ///
+    /// let b = Box::new_in([0u8; 4096], GcAllocator);
+    /// let gc: Gc<[u8]> = Gc::from_box(b);
+    /// assert_eq!(gc.len(), 4096);
+    /// ```
+    #[unstable(feature = "gc", issue = "none")]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
+    pub fn from_box(b: Box<T, GcAllocator>) -> Gc<T> {
+        let (raw, _) = Box::into_raw_with_allocator(b);
+        // `GcBox` is `repr(transparent)`, so the allocation already has the
+        // layout of a `GcBox<T>`.
+        let ptr = raw as *mut GcBox<T>;
+        unsafe {
+            Gc::finalize_unsized(ptr);
+            Gc::from_ptr(ptr)
+        }
+    }
+
+    /// Registers a finalizer for an initialized `GcBox<T>` where `T` may be
+    /// unsized, if `T` needs finalizing.
+    unsafe fn finalize_unsized(ptr: *mut GcBox<T>) {
+        #[cfg(not(bootstrap))]
+        if !crate::mem::needs_finalizer::<T>() {
+            return;
+        }
+
+        if unsafe { mem::size_of_val_raw(ptr) } == 0 {
+            // Zero-sized values don't point into the GC heap.
+            return;
+        }
+
+        unsafe { Gc::register_unsized_finalizer(ptr) };
+    }
+
+    /// Registers a finalizer for a `GcBox<T>` where `T` may be unsized.
+    ///
+    /// The pointer metadata (e.g. a slice length or vtable) cannot be
+    /// recovered from the thin object pointer passed to the finalizer, so it
+    /// is smuggled through the collector's client data instead.
+    unsafe fn register_unsized_finalizer(ptr: *mut GcBox<T>) {
+        assert!(mem::size_of::<<T as Pointee>::Metadata>() <= mem::size_of::<*mut u8>());
+
+        unsafe extern "C" fn unsized_finalizer_shim<T: ?Sized>(obj: *mut u8, data: *mut u8) {
+            let meta: <T as Pointee>::Metadata = unsafe { mem::transmute_copy(&data) };
+            let ptr: *mut GcBox<T> = ptr::from_raw_parts_mut(obj as *mut (), meta);
+            let drop_fn = <GcBox<T> as FinalizeGlue>::finalize_in_place;
+            run_finalizer::<T>(obj, || unsafe { drop_fn(ptr) });
+        }
+
+        let mut data: *mut u8 = ptr::null_mut();
+        unsafe {
+            ptr::write(&mut data as *mut *mut u8 as *mut _, ptr::metadata(ptr));
+            register_finalizer::<T>(ptr as *mut u8, unsized_finalizer_shim::<T>, data);
+        }
+    }
+}
+
+impl<T: Send + Sync + ReferenceFree> Gc<T> {
+    /// Constructs a new `Pin<Gc<T>>`.
+    ///
+    /// This is always sound because the collector never moves objects: the
+    /// value stays at the same address until it is finalized and freed.
+    ///
+    /// [`Pin`]'s drop guarantee still holds when the finalizer for `T` is
+    /// elided (because [`needs_finalizer`] is `false` for it), but only in
+    /// the same way as it does for a leaked value: `T`'s memory is reused
+    /// without its drop method being called. This is only the case for
+    /// types whose drop glue does nothing but free memory owned by the
+    /// value, or which implement [`FinalizerOptional`]. A `!Unpin` type
+    /// which relies on its drop method running before its memory is reused
+    /// (e.g. a node in an intrusive linked list) must not implement
+    /// `FinalizerOptional`.
+    ///
+    /// [`needs_finalizer`]: crate::mem::needs_finalizer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let pinned = Gc::pin(5);
+    /// assert_eq!(*pinned, 5);
+    /// ```
+    #[cfg(not(no_global_oom_handling))]
+    #[unstable(feature = "gc", issue = "none")]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
+    pub fn pin(value: T) -> Pin<Gc<T>> {
+        unsafe { Pin::new_unchecked(Gc::new(value)) }
+    }
+}
+
+impl<T> Gc<[T]> {
+    /// Allocates an uninitialized `GcBox<[T]>` with space for `len` elements.
+    #[cfg(not(no_global_oom_handling))]
+    fn allocate_for_slice(len: usize) -> NonNull<GcBox<[T]>> {
+        let layout = Layout::array::<T>(len).unwrap();
+        let ptr = match Allocator::allocate(&GcAllocator, layout) {
+            Ok(ptr) => ptr.cast::<T>(),
+            Err(_) => handle_alloc_error(layout),
+        };
+        // `GcBox` is `repr(transparent)`, so it has the same layout as `[T]`.
+        let slice = ptr::slice_from_raw_parts_mut(ptr.as_ptr(), len) as *mut GcBox<[T]>;
+        unsafe { NonNull::new_unchecked(slice) }
+    }
+
+    /// Registers a finalizer which drops each element of an initialized
+    /// `GcBox<[T]>`, if `T` needs finalizing.
+    ///
+    /// Unlike `finalizer_shim`, the length of the slice is not recoverable
+    /// from the object pointer alone, so it is passed to the finalizer via the
+    /// collector's client data.
+    #[cfg(not(no_global_oom_handling))]
+    unsafe fn finalize_slice(ptr: NonNull<GcBox<[T]>>, len: usize) {
+        #[cfg(not(bootstrap))]
+        if !crate::mem::needs_finalizer::<T>() {
+            return;
+        }
+
+        if len == 0 {
+            // Zero-length slices don't point into the GC heap.
+            return;
+        }
+
+        unsafe extern "C" fn slice_finalizer_shim<T>(obj: *mut u8, len: *mut u8) {
+            let slice = ptr::slice_from_raw_parts_mut(obj as *mut T, len.addr());
+            run_finalizer::<[T]>(obj, || unsafe { drop_in_place(slice as *mut GcBox<[T]>) });
+        }
+
+        unsafe {
+            register_finalizer::<[T]>(
+                ptr.as_ptr() as *mut u8,
+                slice_finalizer_shim::<T>,
+                ptr::without_provenance_mut(len),
+            );
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FinalizerInfo {
     pub registered: u64,
     pub completed: u64,
 }
 
-#[cfg(profile_gc)]
 impl FinalizerInfo {
     pub fn finalizer_info() -> FinalizerInfo {
         FinalizerInfo {
-            registered: FINALIZERS_REGISTERED.load(atomic::Ordering::Relaxed),
-            completed: FINALIZERS_COMPLETED.load(atomic::Ordering::Relaxed),
+            registered: GC_COUNTERS.finalizers_registered.load(atomic::Ordering::Relaxed),
+            completed: unsafe { bdwgc::GC_finalized_total() },
+        }
+    }
+}
+
+impl Gc<dyn Any> {
+    /// Attempt to downcast the `Gc<dyn Any>` to a concrete type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::any::Any;
+    /// use std::gc::Gc;
+    ///
+    /// fn print_if_string(value: Gc<dyn Any>) {
+    ///     if let Ok(string) = value.downcast::<String>() {
+    ///         println!("String ({}): {}", string.len(), string);
+    ///     }
+    /// }
+    ///
+    /// let my_string = "Hello World".to_string();
+    /// print_if_string(Gc::new(my_string));
+    /// print_if_string(Gc::new(0i8));
+    /// ```
+    #[inline]
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn downcast<T: Any>(self) -> Result<Gc<T>, Gc<dyn Any>> {
+        if (*self).is::<T>() {
+            unsafe {
+                let ptr = self.ptr.cast::<GcBox<T>>();
+                Ok(Gc::from_inner(ptr))
+            }
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Downcasts the `Gc<dyn Any>` to a concrete type.
+    ///
+    /// For a safe alternative see [`downcast`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// #![feature(downcast_unchecked)]
+    ///
+    /// use std::any::Any;
+    /// use std::gc::Gc;
+    ///
+    /// let x: Gc<dyn Any> = Gc::new(1_usize);
+    ///
+    /// unsafe {
+    ///     assert_eq!(*x.downcast_unchecked::<usize>(), 1);
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The contained value must be of type `T`. Calling this method
+    /// with the incorrect type is *undefined behavior*.
+    ///
+    ///
+    /// [`downcast`]: Self::downcast
+    #[inline]
+    #[unstable(feature = "downcast_unchecked", issue = "90850")]
+    pub unsafe fn downcast_unchecked<T: Any>(self) -> Gc<T> {
+        unsafe {
+            let ptr = self.ptr.cast::<GcBox<T>>();
+            Gc::from_inner(ptr)
         }
     }
 }
 
-impl Gc<dyn Any> {
-    /// Attempt to downcast the `Gc<dyn Any>` to a concrete type.
+impl Gc<dyn Any + Send + Sync> {
+    /// Attempt to downcast the `Gc<dyn Any + Send + Sync>` to a concrete type.
     ///
     /// # Examples
     ///
@@ -475,7 +1863,7 @@ impl Gc<dyn Any> {
     /// use std::any::Any;
     /// use std::gc::Gc;
     ///
-    /// fn print_if_string(value: Gc<dyn Any>) {
+    /// fn print_if_string(value: Gc<dyn Any + Send + Sync>) {
     ///     if let Ok(string) = value.downcast::<String>() {
     ///         println!("String ({}): {}", string.len(), string);
     ///     }
@@ -487,7 +1875,7 @@ impl Gc<dyn Any> {
     /// ```
     #[inline]
     #[unstable(feature = "gc", issue = "none")]
-    pub fn downcast<T: Any>(self) -> Result<Gc<T>, Gc<dyn Any>> {
+    pub fn downcast<T: Any + Send + Sync>(self) -> Result<Gc<T>, Self> {
         if (*self).is::<T>() {
             unsafe {
                 let ptr = self.ptr.cast::<GcBox<T>>();
@@ -498,36 +1886,19 @@ pub fn downcast<T: Any>(self) -> Result<Gc<T>, Gc<dyn Any>> {
         }
     }
 
-    /// Downcasts the `Gc<dyn Any>` to a concrete type.
+    /// Downcasts the `Gc<dyn Any + Send + Sync>` to a concrete type.
     ///
     /// For a safe alternative see [`downcast`].
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// # #![feature(gc)]
-    /// #![feature(downcast_unchecked)]
-    ///
-    /// use std::any::Any;
-    /// use std::gc::Gc;
-    ///
-    /// let x: Gc<dyn Any> = Gc::new(1_usize);
-    ///
-    /// unsafe {
-    ///     assert_eq!(*x.downcast_unchecked::<usize>(), 1);
-    /// }
-    /// ```
-    ///
     /// # Safety
     ///
     /// The contained value must be of type `T`. Calling this method
     /// with the incorrect type is *undefined behavior*.
     ///
-    ///
     /// [`downcast`]: Self::downcast
     #[inline]
     #[unstable(feature = "downcast_unchecked", issue = "90850")]
-    pub unsafe fn downcast_unchecked<T: Any>(self) -> Gc<T> {
+    pub unsafe fn downcast_unchecked<T: Any + Send + Sync>(self) -> Gc<T> {
         unsafe {
             let ptr = self.ptr.cast::<GcBox<T>>();
             Gc::from_inner(ptr)
@@ -573,11 +1944,199 @@ impl<T: Default + Send + Sync + ReferenceFree> Default for Gc<T> {
     /// assert_eq!(*x, 0);
     /// ```
     #[inline]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
     fn default() -> Gc<T> {
         Gc::new(Default::default())
     }
 }
 
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl Default for Gc<str> {
+    /// Creates an empty `str` inside a `Gc`.
+    #[inline]
+    fn default() -> Self {
+        Gc::from("")
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl<T> Default for Gc<[T]> {
+    /// Creates an empty `[T]` inside a `Gc`.
+    #[inline]
+    fn default() -> Self {
+        unsafe { Gc::from_inner(Gc::<[T]>::allocate_for_slice(0)) }
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl<T: Send + Sync + ReferenceFree> From<T> for Gc<T> {
+    /// Moves a `T` into a new `Gc<T>`. This is equivalent to calling
+    /// [`Gc::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let x = 5;
+    /// let gc = Gc::new(5);
+    ///
+    /// assert_eq!(Gc::from(x), gc);
+    /// ```
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
+    fn from(t: T) -> Self {
+        Gc::new(t)
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized + Send + Sync + ReferenceFree> From<Box<T>> for Gc<T> {
+    /// Moves a boxed value into a new `Gc<T>`.
+    ///
+    /// `T` may be unsized, so this can be used to turn a `Box<dyn Trait>` into
+    /// a `Gc<dyn Trait>`. The value is copied into a new allocation on the GC
+    /// heap, and the box's allocation is freed without dropping the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let b: Box<[u64]> = Box::new([1, 2, 3]);
+    /// let gc: Gc<[u64]> = Gc::from(b);
+    /// assert_eq!(&*gc, &[1, 2, 3]);
+    /// ```
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
+    fn from(b: Box<T>) -> Self {
+        let layout = Layout::for_value::<T>(&*b);
+        let src = Box::into_raw(b);
+        let dst = match Allocator::allocate(&GcAllocator, layout) {
+            Ok(ptr) => ptr.cast::<u8>(),
+            Err(_) => handle_alloc_error(layout),
+        };
+        // `GcBox` is `repr(transparent)`, so it has the same layout as `T`.
+        let ptr: *mut GcBox<T> =
+            ptr::from_raw_parts_mut(dst.as_ptr() as *mut (), ptr::metadata(src));
+        unsafe {
+            ptr::copy_nonoverlapping(src as *const u8, dst.as_ptr(), layout.size());
+            // The value has been moved, so only free the box's allocation.
+            if layout.size() != 0 {
+                crate::alloc::dealloc(src as *mut u8, layout);
+            }
+            Gc::finalize_unsized(ptr);
+            Gc::from_ptr(ptr)
+        }
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl From<&str> for Gc<str> {
+    /// Allocates a garbage collected string slice and copies `v` into it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let shared: Gc<str> = Gc::from("eggplant");
+    /// assert_eq!("eggplant", &shared[..]);
+    /// ```
+    #[inline]
+    fn from(v: &str) -> Gc<str> {
+        let bytes: Gc<[u8]> = Gc::from(v.as_bytes());
+        unsafe { Gc::from_inner(NonNull::new_unchecked(bytes.ptr.as_ptr() as *mut GcBox<str>)) }
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl From<String> for Gc<str> {
+    /// Allocates a garbage collected string slice and copies `v` into it.
+    #[inline]
+    fn from(v: String) -> Gc<str> {
+        Gc::from(&v[..])
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl<T: Clone + Send + Sync + ReferenceFree> From<&[T]> for Gc<[T]> {
+    /// Allocates a garbage collected slice and fills it by cloning `v`'s
+    /// items.
+    ///
+    /// Elements are finalized individually if `T` needs finalizing. As with
+    /// [`Gc::new`], finalizer safety analysis checks that `T`'s drop glue can
+    /// be run by a finalizer at each call site.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let original: &[i32] = &[1, 2, 3];
+    /// let shared: Gc<[i32]> = Gc::from(original);
+    /// assert_eq!(&[1, 2, 3], &shared[..]);
+    /// ```
+    #[inline]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
+    fn from(v: &[T]) -> Gc<[T]> {
+        let ptr = Gc::<[T]>::allocate_for_slice(v.len());
+        let elems = ptr.as_ptr() as *mut T;
+        for (i, item) in v.iter().enumerate() {
+            // If `clone` panics, the elements written so far are leaked. This
+            // is safe, and no finalizer has been registered yet.
+            unsafe { elems.add(i).write(item.clone()) };
+        }
+        unsafe {
+            Gc::<[T]>::finalize_slice(ptr, v.len());
+            Gc::from_inner(ptr)
+        }
+    }
+}
+
+#[cfg(not(no_global_oom_handling))]
+#[unstable(feature = "gc", issue = "none")]
+impl<T: Send + Sync + ReferenceFree> From<Vec<T>> for Gc<[T]> {
+    /// Allocates a garbage collected slice and moves `v`'s items into it.
+    ///
+    /// Elements are finalized individually if `T` needs finalizing. As with
+    /// [`Gc::new`], finalizer safety analysis checks that `T`'s drop glue can
+    /// be run by a finalizer at each call site.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(gc)]
+    /// use std::gc::Gc;
+    ///
+    /// let unique: Vec<i32> = vec![1, 2, 3];
+    /// let shared: Gc<[i32]> = Gc::from(unique);
+    /// assert_eq!(&[1, 2, 3], &shared[..]);
+    /// ```
+    #[inline]
+    #[cfg_attr(not(bootstrap), rustc_gc_constructor)]
+    fn from(mut v: Vec<T>) -> Gc<[T]> {
+        let len = v.len();
+        let ptr = Gc::<[T]>::allocate_for_slice(len);
+        unsafe {
+            ptr::copy_nonoverlapping(v.as_ptr(), ptr.as_ptr() as *mut T, len);
+            // The elements have been moved, so only free the `Vec`'s buffer.
+            v.set_len(0);
+            Gc::<[T]>::finalize_slice(ptr, len);
+            Gc::from_inner(ptr)
+        }
+    }
+}
+
 impl<T: ?Sized + PartialEq> PartialEq for Gc<T> {
     /// Equality for two `Gc`s.
     ///
@@ -818,3 +2377,243 @@ fn as_ref(&self) -> &T {
         &**self
     }
 }
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: crate::error::Error + ?Sized> crate::error::Error for Gc<T> {
+    #[allow(deprecated, deprecated_in_future)]
+    fn description(&self) -> &str {
+        crate::error::Error::description(&**self)
+    }
+
+    #[allow(deprecated)]
+    fn cause(&self) -> Option<&dyn crate::error::Error> {
+        crate::error::Error::cause(&**self)
+    }
+
+    fn source(&self) -> Option<&(dyn crate::error::Error + 'static)> {
+        crate::error::Error::source(&**self)
+    }
+
+    fn provide<'a>(&'a self, req: &mut crate::error::Request<'a>) {
+        crate::error::Error::provide(&**self, req);
+    }
+}
+
+/// A wrapper around a [`Gc`] which compares, orders, and hashes by the
+/// identity of the allocation it points to rather than by its value.
+///
+/// This is useful for using `Gc`s as keys in a `HashMap` or `BTreeMap` where
+/// `T` is not hashable, or where `T` has interior mutability so its value can
+/// change while it is in the map.
+///
+/// Only the address of the allocation is considered: any metadata on a fat
+/// pointer (such as a slice length or a vtable) is ignored. This is consistent
+/// with [`Gc::ptr_eq`].
+///
+/// # Examples
+///
+/// ```
+/// # #![feature(gc)]
+/// use std::cell::Cell;
+/// use std::collections::HashSet;
+/// use std::gc::{Gc, GcByAddress};
+///
+/// let a = Gc::new(Cell::new(1));
+/// let b = Gc::new(Cell::new(1));
+///
+/// let mut set = HashSet::new();
+/// set.insert(GcByAddress(a));
+/// set.insert(GcByAddress(b));
+/// set.insert(GcByAddress(a));
+/// assert_eq!(set.len(), 2);
+///
+/// a.set(2);
+/// assert!(set.contains(&GcByAddress(a)));
+/// ```
+#[unstable(feature = "gc", issue = "none")]
+#[repr(transparent)]
+pub struct GcByAddress<T: ?Sized>(pub Gc<T>);
+
+impl<T: ?Sized> GcByAddress<T> {
+    #[inline(always)]
+    fn addr(&self) -> usize {
+        self.0.ptr.as_ptr().cast::<u8>().addr()
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Copy for GcByAddress<T> {}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Clone for GcByAddress<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> PartialEq for GcByAddress<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Gc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Eq for GcByAddress<T> {}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> PartialOrd for GcByAddress<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Ord for GcByAddress<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Hash for GcByAddress<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state);
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> Deref for GcByAddress<T> {
+    type Target = Gc<T>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Gc<T> {
+        &self.0
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> From<Gc<T>> for GcByAddress<T> {
+    fn from(gc: Gc<T>) -> Self {
+        GcByAddress(gc)
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T: ?Sized> fmt::Debug for GcByAddress<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GcByAddress").field(&self.0.ptr).finish()
+    }
+}
+
+/// A pointer to a component of a value owned by a [`Gc`].
+///
+/// A `GcProjection` is created with [`Gc::map`]. It holds a pointer to the base
+/// of the `Gc` allocation it was derived from, so the whole object (and every
+/// value reachable from it) stays alive for as long as the projection does.
+/// `T` is the type of that base object.
+///
+/// Like `Gc`, it is `Copy`, and it dereferences to the projected value. A
+/// projection can only be sent to (or shared with) another thread if both the
+/// projected value and the base object could be, since it keeps the base
+/// object alive there.
+#[unstable(feature = "gc", issue = "none")]
+pub struct GcProjection<U: ?Sized, T: ?Sized> {
+    /// The originating `Gc`. This is never dereferenced: it exists so that the
+    /// collector sees a pointer to the start of the object, even if the
+    /// projected pointer is to memory outside of the allocation (e.g. to the
+    /// contents of a `Box` owned by the base object). Holding a `Gc` also
+    /// means the projection gets the same `keep_alive` barrier on drop.
+    base: Gc<T>,
+    ptr: NonNull<U>,
+}
+
+unsafe impl<U: ?Sized + Send + Sync, T: ?Sized + Send + Sync> Send for GcProjection<U, T> {}
+unsafe impl<U: ?Sized + Send + Sync, T: ?Sized + Send + Sync> Sync for GcProjection<U, T> {}
+
+impl<U: ?Sized, T: ?Sized> !FinalizerSafe for GcProjection<U, T> {}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized + Unsize<V>, V: ?Sized, T: ?Sized> CoerceUnsized<GcProjection<V, T>>
+    for GcProjection<U, T>
+{
+}
+
+impl<U: ?Sized, T: ?Sized> GcProjection<U, T> {
+    /// Makes a new `GcProjection` to a component of the projected value.
+    ///
+    /// The resulting projection keeps the original base object alive.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn map<V: ?Sized, F>(this: Self, f: F) -> GcProjection<V, T>
+    where
+        F: for<'a> FnOnce(&'a U) -> &'a V,
+    {
+        let ptr = NonNull::from(f(&*this));
+        GcProjection { base: this.base, ptr }
+    }
+
+    /// Get a raw pointer to the projected value.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn as_ptr(this: &Self) -> *const U {
+        this.ptr.as_ptr()
+    }
+
+    /// Returns `true` if both projections point to the same value.
+    #[unstable(feature = "gc", issue = "none")]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        crate::ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized, T: ?Sized> Copy for GcProjection<U, T> {}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized, T: ?Sized> Clone for GcProjection<U, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized, T: ?Sized> Deref for GcProjection<U, T> {
+    type Target = U;
+
+    #[inline(always)]
+    fn deref(&self) -> &U {
+        // SAFETY: `ptr` was derived from a reference into an object which is
+        // kept alive by `base`.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<T> From<Gc<T>> for GcProjection<T, T> {
+    fn from(gc: Gc<T>) -> Self {
+        Gc::map(gc, |t| t)
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized + fmt::Display, T: ?Sized> fmt::Display for GcProjection<U, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized + fmt::Debug, T: ?Sized> fmt::Debug for GcProjection<U, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[unstable(feature = "gc", issue = "none")]
+impl<U: ?Sized, T: ?Sized> fmt::Pointer for GcProjection<U, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr, f)
+    }
+}
@@ -34,3 +34,160 @@ fn f(&self) -> u64 {
     assert_eq!(s1gcd.f(), 1);
     assert_eq!(s2gcd.f(), 2);
 }
+
+#[test]
+fn test_by_address() {
+    use crate::collections::hash_map::DefaultHasher;
+
+    fn hash<T: Hash>(t: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        t.hash(&mut h);
+        h.finish()
+    }
+
+    let a = Gc::new(1);
+    let b = Gc::new(1);
+    assert_ne!(GcByAddress(a), GcByAddress(b));
+    assert_eq!(GcByAddress(a), GcByAddress(a));
+    assert_eq!(hash(&GcByAddress(a)), hash(&GcByAddress(a)));
+
+    // Unsizing a `Gc` must not change its identity.
+    let s: Gc<[u64; 3]> = Gc::new([1, 2, 3]);
+    let s1: Gc<[u64]> = s;
+    let s2: Gc<[u64]> = s;
+    assert_eq!(GcByAddress(s1), GcByAddress(s2));
+    assert_eq!(hash(&GcByAddress(s1)), hash(&GcByAddress(s2)));
+
+    let d1: Gc<dyn crate::fmt::Debug> = a;
+    let d2: Gc<dyn crate::fmt::Debug> = a;
+    assert_eq!(GcByAddress(d1), GcByAddress(d2));
+    assert_eq!(GcByAddress(d1).cmp(&GcByAddress(d2)), Ordering::Equal);
+}
+
+#[test]
+fn test_projection() {
+    struct Pair {
+        a: u64,
+        b: [u64; 2],
+    }
+
+    let p = Gc::new(Pair { a: 1, b: [2, 3] });
+    let a = Gc::map(p, |p| &p.a);
+    let b = Gc::map(p, |p| &p.b);
+    let b1 = GcProjection::map(b, |b| &b[1]);
+    assert_eq!(*a, 1);
+    assert_eq!(*b1, 3);
+
+    let bs: GcProjection<[u64], Pair> = b;
+    assert_eq!(bs.len(), 2);
+
+    let b1_copy = b1;
+    assert!(GcProjection::ptr_eq(&b1, &b1_copy));
+    assert_eq!(GcProjection::as_ptr(&b1), &p.b[1] as *const u64);
+}
+
+#[test]
+fn test_projection_keeps_base_alive() {
+    use crate::sync::atomic::AtomicUsize;
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Tracked {
+        value: u64,
+    }
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, atomic::Ordering::Relaxed);
+        }
+    }
+
+    const COUNT: usize = 10;
+
+    #[inline(never)]
+    fn project() -> Vec<GcProjection<u64, Tracked>> {
+        (0..COUNT).map(|i| Gc::map(Gc::new(Tracked { value: i as u64 }), |t| &t.value)).collect()
+    }
+
+    let projections = project();
+    GcAllocator::force_gc();
+    thread::sleep(Duration::from_millis(100));
+    // Only the projections refer to the base objects now, so if they didn't
+    // keep them alive, the collector would have finalized them.
+    assert_eq!(DROPPED.load(atomic::Ordering::Relaxed), 0);
+    for (i, p) in projections.iter().enumerate() {
+        assert_eq!(**p, i as u64);
+    }
+}
+
+#[test]
+fn test_from_conversions() {
+    let s: Gc<str> = Gc::from(String::from("hello"));
+    assert_eq!(&*s, "hello");
+    let empty: Gc<str> = Default::default();
+    assert_eq!(&*empty, "");
+
+    let v: Gc<[String]> = Gc::from(vec![String::from("a"), String::from("b")]);
+    assert_eq!(v.len(), 2);
+    assert_eq!(v[1], "b");
+    let cloned: Gc<[u64]> = Gc::from(&[1u64, 2, 3][..]);
+    assert_eq!(&*cloned, &[1, 2, 3]);
+    let empty: Gc<[u64]> = Default::default();
+    assert!(empty.is_empty());
+
+    let b: Gc<u64> = Gc::from(Box::new(5));
+    assert_eq!(*b, 5);
+    let b: Box<[String]> = vec![String::from("c"), String::from("d")].into_boxed_slice();
+    let b: Gc<[String]> = Gc::from(b);
+    assert_eq!(&*b, &["c", "d"]);
+}
+
+#[test]
+fn test_downcast_send_sync() {
+    let any: Gc<dyn Any + Send + Sync> = Gc::new(5u64);
+    let any = any.downcast::<u32>().unwrap_err();
+    assert_eq!(*any.downcast::<u64>().unwrap(), 5);
+}
+
+#[test]
+fn test_from_box() {
+    trait Len: Send + Sync + FinalizerSafe + ReferenceFree {
+        fn len(&self) -> usize;
+    }
+    impl Len for Vec<u64> {
+        fn len(&self) -> usize {
+            Vec::len(self)
+        }
+    }
+
+    let b = Box::new_in(vec![1u64, 2, 3], GcAllocator);
+    let raw = &*b as *const Vec<u64>;
+    let gc = Gc::from_box(b);
+    assert_eq!(Gc::as_ptr(&gc), raw);
+    assert_eq!(*gc, [1, 2, 3]);
+
+    let b: Box<dyn Len, GcAllocator> = Box::new_in(vec![1u64, 2], GcAllocator);
+    let gc: Gc<dyn Len> = Gc::from_box(b);
+    assert_eq!(gc.len(), 2);
+
+    let b: Box<[u8], GcAllocator> = Box::new_in([7u8; 3], GcAllocator);
+    let gc: Gc<[u8]> = Gc::from_box(b);
+    assert_eq!(&*gc, &[7, 7, 7]);
+}
+
+#[test]
+fn test_finalizer_profile_registration() {
+    struct Profiled(#[allow(dead_code)] u64);
+    impl Drop for Profiled {
+        fn drop(&mut self) {}
+    }
+
+    enable_finalizer_profiling();
+    let _a = Gc::new(Profiled(1));
+    let _b = Gc::new(Profiled(2));
+    disable_finalizer_profiling();
+    let _c = Gc::new(Profiled(3));
+
+    let profile = finalizer_profile();
+    let p = profile.iter().find(|p| p.type_name.ends_with("Profiled")).unwrap();
+    assert_eq!(p.registered, 2);
+}
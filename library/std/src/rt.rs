@@ -114,6 +114,7 @@ unsafe fn init(argc: isize, argv: *const *const u8, sigpipe: u8) {
 pub(crate) fn cleanup() {
     static CLEANUP: Once = Once::new();
     CLEANUP.call_once(|| unsafe {
+        crate::gc::cleanup();
         // Flush stdout and disable buffering.
         crate::io::cleanup();
         // SAFETY: Only called once during runtime cleanup.
@@ -0,0 +1,4 @@
+#![crate_type = "lib"]
+
+#[finalizer_optional] //~ ERROR the `#[finalizer_optional]` attribute is an experimental feature
+pub struct Buffer(Vec<u8>);
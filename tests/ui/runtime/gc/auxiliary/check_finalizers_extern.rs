@@ -0,0 +1,48 @@
+#![feature(gc)]
+
+use std::gc::Gc;
+
+pub struct Logger(pub *mut u8);
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        println!("Dropping Logger");
+    }
+}
+
+pub struct HasGcFields(pub Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+// The MIR of these helpers is not encoded, so downstream crates can only check
+// them through their summaries.
+
+pub struct Counter(pub usize, pub *mut u8);
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        report(self);
+    }
+}
+
+#[inline(never)]
+fn report(counter: &Counter) {
+    println!("Counter {}", counter.0);
+}
+
+pub struct GcLogger(pub Gc<usize>);
+
+impl Drop for GcLogger {
+    fn drop(&mut self) {
+        log(self);
+    }
+}
+
+#[inline(never)]
+fn log(logger: &GcLogger) {
+    println!("GcLogger {}", logger.0);
+}
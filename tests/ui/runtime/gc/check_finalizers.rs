@@ -50,7 +50,7 @@ impl !Sync for NotThreadSafe {}
 
 impl ShouldFail2 {
     #[inline(never)]
-    fn foo(&mut self) {}
+    fn foo(&mut self) { println!("{:?}", self.0); }
 }
 
 impl Drop for ShouldFail2 {
@@ -80,4 +80,79 @@ fn main() {
     Gc::new(not_threadsafe); //~ ERROR: `not_threadsafe` cannot be safely finalized.
 
     unsafe { Gc::new(FinalizeUnchecked::new(ShouldFail(Cell::new(123)))) };
+
+    // Helper methods called with `self` are only rejected if they do
+    // something unsound themselves.
+    Gc::new(HelperCall(123, 123 as *mut u8, NotFinalizerSafe(123)));
+
+    // Values created inside `drop` are not part of the object being
+    // finalized, so their fields can be used freely.
+    Gc::new(LocalValue(123 as *mut u8));
+
+    Gc::new(UsesHandle(Handle(123 as *mut u8))); //~ ERROR: `UsesHandle(Handle(123 as *mut u8))` cannot be safely finalized.
+    Gc::new(PassesGcField(Gc::new(123))); //~ ERROR: `PassesGcField(Gc::new(123))` cannot be safely finalized.
+}
+
+// The helpers only use the first field, so the others are never used in a
+// finalizer, even though they could not be.
+struct HelperCall(usize, *mut u8, NotFinalizerSafe);
+
+struct NotFinalizerSafe(usize);
+
+impl !FinalizerSafe for NotFinalizerSafe {}
+
+impl HelperCall {
+    #[inline(never)]
+    fn close(&self) {
+        self.log(self.0);
+    }
+
+    #[inline(never)]
+    fn log(&self, n: usize) {
+        if n > 0 {
+            // Recursion must not stop the analysis from terminating.
+            self.log(n - 1);
+        }
+    }
+}
+
+impl Drop for HelperCall {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+struct LocalValue(*mut u8);
+
+impl Drop for LocalValue {
+    fn drop(&mut self) {
+        let mut x = ShouldFail2(456 as *mut u8);
+        x.foo();
+    }
+}
+
+#[derive(Debug)]
+struct Handle(*mut u8);
+
+unsafe impl Send for Handle {}
+unsafe impl Sync for Handle {}
+
+struct UsesHandle(Handle);
+
+impl Drop for UsesHandle {
+    fn drop(&mut self) {
+        println!("{:?}", self.0);
+    }
+}
+
+struct PassesGcField(Gc<usize>);
+
+#[inline(never)]
+fn takes(_: Gc<usize>) {}
+
+impl Drop for PassesGcField {
+    // Passing the field straight to a function still uses it.
+    fn drop(&mut self) {
+        takes(self.0);
+    }
 }
@@ -0,0 +1,26 @@
+#![feature(gc)]
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+fn main() {
+    // Dropping a `Box`, `Vec` or `VecDeque` only drops the values which it
+    // owns, so these are fine even though they are not `Send + Sync`.
+    Gc::new(Box::new(Cell::new(1u8)));
+    Gc::new(Box::new(vec![Cell::new(1u8)]));
+    Gc::new(Box::new(vec![Box::new(123 as *mut u8)]));
+    Gc::new(VecDeque::from([Box::new(Cell::new(1u8))]));
+
+    // But the values they own must still be safe to finalize.
+    Gc::new(Box::new(Box::new(HasGcFields(Gc::new(1))))); //~ ERROR: `Box::new(Box::new(HasGcFields(Gc::new(1))))` cannot be safely finalized.
+    Gc::new(Box::new(vec![HasGcFields(Gc::new(1))])); //~ ERROR: `Box::new(vec![HasGcFields(Gc::new(1))])` cannot be safely finalized.
+}
@@ -0,0 +1,20 @@
+#![feature(gc)]
+
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+fn main() {
+    let captured = HasGcFields(Gc::new(123));
+    Gc::new(move || { let _x = &captured; }); //~ ERROR: `move || { let _x = &captured; }` cannot be safely finalized.
+
+    // Captured values which are safe to finalize are fine.
+    let n = 123;
+    Gc::new(move || n + 1);
+}
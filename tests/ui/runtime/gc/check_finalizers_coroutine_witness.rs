@@ -0,0 +1,22 @@
+//@ build-fail
+//@ edition:2021
+#![feature(gc)]
+
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+async fn suspend() {}
+
+fn main() {
+    // `x` is held across a suspension point, so it is part of the coroutine's
+    // witness. This is only checked once the coroutine has been borrow checked,
+    // and so not in a check-only build.
+    Gc::new(async { let x = HasGcFields(Gc::new(1)); suspend().await; }); //~ ERROR: `async { let x = HasGcFields(Gc::new(1)); suspend().await; }` cannot be safely finalized.
+}
@@ -0,0 +1,21 @@
+//@ check-fail
+//@ edition:2021
+#![feature(gc)]
+
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+fn main() {
+    let captured = HasGcFields(Gc::new(123));
+    Gc::new(async move { let _x = &captured; }); //~ ERROR: `async move { let _x = &captured; }` cannot be safely finalized.
+
+    let n = 123;
+    Gc::new(async move { n + 1 });
+}
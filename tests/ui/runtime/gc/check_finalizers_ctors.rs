@@ -0,0 +1,59 @@
+#![feature(gc)]
+#![feature(negative_impls)]
+
+use std::cell::Cell;
+use std::gc::Gc;
+
+#[derive(Default)]
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+struct NotThreadSafe(usize);
+
+impl !Send for NotThreadSafe {}
+impl !Sync for NotThreadSafe {}
+
+struct Local(NotThreadSafe);
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0.0);
+    }
+}
+
+fn main() {
+    let gcfields = HasGcFields(Gc::new(123));
+    let _: Gc<HasGcFields> = Gc::from(gcfields); //~ ERROR: `gcfields` cannot be safely finalized.
+
+    let _: Gc<HasGcFields> = Default::default(); //~ ERROR: `Default::default()` cannot be safely finalized.
+
+    let unsync = HasGcFields(Gc::new(123));
+    let _ = unsafe { Gc::new_unsynchronised(unsync) }; //~ ERROR: `unsync` cannot be safely finalized.
+
+    let unfinalizable = HasGcFields(Gc::new(123));
+    let _ = Gc::new_unfinalizable(unfinalizable);
+
+    // Local finalizers run on the allocating thread, so they don't need to be
+    // `Send` or `Sync`.
+    let local = Local(NotThreadSafe(123));
+    let _ = Gc::new_local(local);
+
+    let gcvec = vec![HasGcFields(Gc::new(123))];
+    let _: Gc<[HasGcFields]> = Gc::from(gcvec); //~ ERROR: `gcvec` cannot be safely finalized.
+
+    // `Gc::new_unsynchronised` only relaxes the `Sync` requirement.
+    let _ = unsafe { Gc::new_unsynchronised(Unsync(Cell::new(123))) };
+}
+
+struct Unsync(Cell<usize>);
+
+impl Drop for Unsync {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0.get());
+    }
+}
@@ -0,0 +1,42 @@
+#![feature(gc)]
+
+use std::gc::Gc;
+
+struct HasRef<'a>(&'a mut Vec<u8>);
+
+impl Drop for HasRef<'_> {
+    fn drop(&mut self) {}
+}
+
+// A `'static` reference can't dangle, however long the value lives.
+struct Named {
+    name: &'static str,
+}
+
+impl Drop for Named {
+    fn drop(&mut self) {}
+}
+
+struct Outer<'a> {
+    inner: Inner<'a>,
+}
+
+impl Drop for Outer<'_> {
+    fn drop(&mut self) {}
+}
+
+struct Inner<'a> {
+    names: Vec<&'static str>,
+    buf: &'a [u8],
+}
+
+fn main() {
+    let mut v = Vec::new();
+    Gc::new(HasRef(&mut v)); //~ ERROR: `HasRef(&mut v)` cannot be safely constructed.
+
+    Gc::new(Named { name: "log" });
+
+    let buf = [0; 4];
+    let outer = Outer { inner: Inner { names: vec!["log"], buf: &buf } };
+    Gc::new(outer); //~ ERROR: `outer` cannot be safely constructed.
+}
@@ -0,0 +1,83 @@
+#![feature(gc)]
+
+use std::gc::Gc;
+use std::marker::FinalizerSafe;
+
+trait Bounded: Send + Sync + FinalizerSafe {
+    fn get(&self) -> usize;
+}
+
+trait Unbounded {
+    fn get(&self) -> usize;
+}
+
+struct Value(usize);
+
+impl Bounded for Value {
+    fn get(&self) -> usize {
+        self.0
+    }
+}
+
+impl Unbounded for Value {
+    fn get(&self) -> usize {
+        self.0
+    }
+}
+
+// Trait objects whose bounds guarantee that they can be finalized safely are
+// fine, whether the bounds come from supertraits or are listed explicitly.
+struct HasBoundedDyn(Box<dyn Bounded>, *mut u8);
+
+impl Drop for HasBoundedDyn {
+    fn drop(&mut self) {
+        let _ = self.0.get();
+    }
+}
+
+struct HasAutoBoundedDyn(Box<dyn Unbounded + Send + Sync + FinalizerSafe>, *mut u8);
+
+impl Drop for HasAutoBoundedDyn {
+    fn drop(&mut self) {
+        let _ = self.0.get();
+    }
+}
+
+struct HasUnboundedDyn(Box<dyn Unbounded>);
+
+struct UsesGc(Gc<usize>);
+
+impl Unbounded for UsesGc {
+    fn get(&self) -> usize {
+        0
+    }
+}
+
+impl Drop for UsesGc {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+fn unknown_source(b: Box<dyn Unbounded>) {
+    // We can't see which concrete type was unsized into this trait object.
+    Gc::new(HasUnboundedDyn(b)); //~ ERROR: `HasUnboundedDyn(b)` cannot be safely finalized.
+}
+
+fn main() {
+    Gc::new(HasBoundedDyn(Box::new(Value(1)), std::ptr::null_mut()));
+    Gc::new(HasAutoBoundedDyn(Box::new(Value(1)), std::ptr::null_mut()));
+
+    // Otherwise, the concrete types which were unsized into the trait object
+    // are checked instead.
+    Gc::new(HasUnboundedDyn(Box::new(Value(1))));
+    Gc::new(HasUnboundedDyn(Box::new(UsesGc(Gc::new(1))))); //~ ERROR: `HasUnboundedDyn(Box::new(UsesGc(Gc::new(1))))` cannot be safely finalized.
+
+    unknown_source(Box::new(Value(1)));
+
+    // A trait object on its own is also accepted when its source is known,
+    // including through a local.
+    Gc::new(Box::new(Value(1)) as Box<dyn Unbounded>);
+    let unsized_local: Box<dyn Unbounded> = Box::new(Value(2));
+    Gc::new(unsized_local);
+}
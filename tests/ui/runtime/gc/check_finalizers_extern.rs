@@ -0,0 +1,18 @@
+//@ aux-build:check_finalizers_extern.rs
+#![feature(gc)]
+
+// The `Drop` impls of upstream types are checked through the summaries of them
+// stored in the upstream crate's metadata, since their MIR is not encoded. This
+// includes the non-generic functions which they pass the object to.
+
+extern crate check_finalizers_extern;
+
+use check_finalizers_extern::{Counter, GcLogger, HasGcFields, Logger};
+use std::gc::Gc;
+
+fn main() {
+    Gc::new(Logger(123 as *mut u8));
+    Gc::new(HasGcFields(Gc::new(1))); //~ ERROR: `HasGcFields(Gc::new(1))` cannot be safely finalized.
+    Gc::new(Counter(1, 123 as *mut u8));
+    Gc::new(GcLogger(Gc::new(1))); //~ ERROR: `GcLogger(Gc::new(1))` cannot be safely finalized.
+}
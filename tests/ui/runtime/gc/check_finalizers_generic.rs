@@ -0,0 +1,31 @@
+//@ build-fail
+#![feature(gc)]
+
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+struct ShouldPass(usize);
+
+impl Drop for ShouldPass {
+    fn drop(&mut self) {
+        println!("Dropping {}", self.0);
+    }
+}
+
+// The bounds alone say nothing about `T`'s drop method, so this can only be
+// checked once `T` is known.
+fn wrap<T: Send + Sync>(t: T) -> Gc<T> {
+    Gc::new(t) //~ ERROR: `t` cannot be safely finalized.
+}
+
+fn main() {
+    wrap(ShouldPass(123));
+    wrap(HasGcFields(Gc::new(123)));
+}
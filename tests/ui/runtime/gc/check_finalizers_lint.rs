@@ -0,0 +1,47 @@
+//@ check-pass
+#![feature(gc)]
+#![feature(lint_reasons)]
+#![warn(unsound_finalizer)]
+
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+struct HasRef<'a>(&'a mut Vec<u8>);
+
+impl Drop for HasRef<'_> {
+    fn drop(&mut self) {}
+}
+
+#[allow(unsound_finalizer)]
+fn allowed() {
+    Gc::new(HasGcFields(Gc::new(1)));
+}
+
+#[expect(unsound_finalizer)]
+fn expected() {
+    Gc::new(HasGcFields(Gc::new(1)));
+}
+
+#[allow(gc_dangling_reference)]
+mod dangling {
+    use super::*;
+
+    pub fn allowed() {
+        let mut v = Vec::new();
+        Gc::new(HasRef(&mut v));
+    }
+}
+
+fn main() {
+    Gc::new(HasGcFields(Gc::new(1))); //~ WARN: `HasGcFields(Gc::new(1))` cannot be safely finalized.
+    allowed();
+    expected();
+    dangling::allowed();
+}
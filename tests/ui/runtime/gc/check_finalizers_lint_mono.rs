@@ -0,0 +1,53 @@
+//@ build-pass
+#![feature(gc)]
+#![feature(lint_reasons)]
+#![allow(dead_code)]
+
+// Calls in generic functions are reported for each instantiation during
+// monomorphization, at the lint levels of the generic function.
+
+use std::gc::Gc;
+
+struct HasGcFields(Gc<usize>);
+
+impl Drop for HasGcFields {
+    fn drop(&mut self) {
+        println!("Boom {}", self.0);
+    }
+}
+
+struct HasRef<'a>(&'a mut Vec<u8>);
+
+impl Drop for HasRef<'_> {
+    fn drop(&mut self) {}
+}
+
+#[allow(unsound_finalizer)]
+fn allowed<T: Send + Sync>(t: T) -> Gc<T> {
+    Gc::new(t)
+}
+
+#[expect(unsound_finalizer)]
+fn expected<T: Send + Sync>(t: T) -> Gc<T> {
+    Gc::new(t)
+}
+
+#[allow(gc_dangling_reference)]
+fn allowed_dangling<T: Send + Sync>(t: T) -> Gc<T> {
+    Gc::new(t)
+}
+
+#[expect(gc_dangling_reference)]
+fn expected_dangling<T: Send + Sync>(t: T) -> Gc<T> {
+    Gc::new(t)
+}
+
+fn main() {
+    allowed(HasGcFields(Gc::new(1)));
+    expected(HasGcFields(Gc::new(2)));
+
+    let mut v = Vec::new();
+    allowed_dangling(HasRef(&mut v));
+    let mut v = Vec::new();
+    expected_dangling(HasRef(&mut v));
+}
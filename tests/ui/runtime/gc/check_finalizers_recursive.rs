@@ -0,0 +1,45 @@
+//@ build-pass
+//@ compile-flags: -O
+#![feature(gc)]
+#![allow(dead_code)]
+
+// A finalizer can call the function whose `Gc` constructor is being checked.
+// Checking it must not need that function's optimized MIR, which isn't built
+// yet (and, with inlining, may need the finalizer's own MIR).
+
+use std::gc::Gc;
+
+struct S(usize);
+
+impl Drop for S {
+    fn drop(&mut self) {
+        if self.0 > 0 {
+            make(self.0 - 1);
+        }
+    }
+}
+
+fn make(n: usize) -> Gc<S> {
+    Gc::new(S(n))
+}
+
+struct T(usize);
+
+impl T {
+    fn make(n: usize) -> Gc<T> {
+        Gc::new(T(n))
+    }
+}
+
+impl Drop for T {
+    fn drop(&mut self) {
+        if self.0 > 0 {
+            T::make(self.0 - 1);
+        }
+    }
+}
+
+fn main() {
+    make(3);
+    T::make(3);
+}
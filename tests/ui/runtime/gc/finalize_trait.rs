@@ -0,0 +1,84 @@
+//@ run-pass
+// ignore-tidy-linelength
+#![feature(gc)]
+#![feature(negative_impls)]
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::gc::{Finalize, Gc, GcAllocator};
+use std::mem;
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time;
+
+// The `Drop` impl is not finalizer safe, but it never runs in a finalizer
+// because `finalize` is used instead, so this is accepted by `Gc::new`.
+struct Finalized(Cell<usize>);
+
+impl !Send for Finalized {}
+
+impl Drop for Finalized {
+    fn drop(&mut self) {
+        self.0.replace(456);
+        DROP_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+}
+
+impl Finalize for Finalized {
+    fn finalize(&mut self) {
+        FINALIZE_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+}
+
+struct NoFields;
+
+impl Finalize for NoFields {
+    fn finalize(&mut self) {}
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+static FINALIZE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_COUNT: usize = 10;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+fn foo() {
+    for i in 0..ALLOCATED_COUNT {
+        {
+            let mut _gc = Some(Gc::new(Finalized(Cell::new(i))));
+
+            // Zero the root to the GC object.
+            _gc = None;
+        }
+    }
+}
+
+fn main() {
+    // Types with a `Finalize` impl always need a finalizer, even if they have
+    // no drop glue.
+    assert!(mem::needs_finalizer::<NoFields>());
+    assert!(mem::needs_finalizer::<Finalized>());
+
+    {
+        let _r = Finalized(Cell::new(0));
+    }
+    assert_eq!(DROP_COUNT.load(atomic::Ordering::Relaxed), 1);
+
+    foo();
+    GcAllocator::force_gc();
+
+    let mut count = FINALIZE_COUNT.load(atomic::Ordering::Relaxed);
+    let mut sleep_duration = 2;
+    while count < ALLOCATED_COUNT - 1 && sleep_duration <= SLEEP_MAX {
+        // Wait an acceptable amount of time for the finalizer thread to do its work.
+        thread::sleep(time::Duration::from_millis(sleep_duration));
+        sleep_duration = sleep_duration * 2;
+        count = FINALIZE_COUNT.load(atomic::Ordering::Relaxed);
+    }
+
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(count >= ALLOCATED_COUNT - 1);
+    assert!(count <= ALLOCATED_COUNT);
+    // Drop glue never runs for values finalized with `Finalize`.
+    assert_eq!(DROP_COUNT.load(atomic::Ordering::Relaxed), 1);
+}
@@ -0,0 +1,58 @@
+//@ run-pass
+#![feature(gc)]
+#![feature(finalizer_optional)]
+#![allow(dead_code)]
+
+use std::alloc::{dealloc, Layout};
+use std::mem;
+use std::ptr;
+
+struct HasDrop;
+
+impl Drop for HasDrop {
+    fn drop(&mut self) {}
+}
+
+// A hand-rolled buffer whose `Drop` impl only drops its elements and frees
+// their memory, so it only needs a finalizer if `T` does.
+#[finalizer_optional]
+struct Buffer<T> {
+    ptr: *mut T,
+    len: usize,
+    cap: usize,
+}
+
+impl<T> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr, self.len));
+            if self.cap != 0 {
+                let size = mem::size_of::<T>() * self.cap;
+                let layout = Layout::from_size_align_unchecked(size, mem::align_of::<T>());
+                dealloc(self.ptr as *mut u8, layout);
+            }
+        }
+    }
+}
+
+// Dropping a field explicitly is also fine.
+#[finalizer_optional]
+struct Slot<T> {
+    value: Option<T>,
+}
+
+impl<T> Drop for Slot<T> {
+    fn drop(&mut self) {
+        mem::drop(self.value.take());
+    }
+}
+
+fn main() {
+    assert!(!mem::needs_finalizer::<Buffer<u8>>());
+    assert!(!mem::needs_finalizer::<Buffer<(usize, &'static str)>>());
+    assert!(mem::needs_finalizer::<Buffer<HasDrop>>());
+    assert!(mem::needs_finalizer::<Buffer<Buffer<HasDrop>>>());
+
+    assert!(!mem::needs_finalizer::<Slot<u8>>());
+    assert!(mem::needs_finalizer::<Slot<HasDrop>>());
+}
@@ -0,0 +1,106 @@
+#![feature(gc)]
+#![feature(finalizer_optional)]
+#![allow(dead_code)]
+
+use std::alloc::{dealloc, Layout};
+use std::ptr::{self, NonNull};
+
+struct Noisy;
+
+impl Drop for Noisy {
+    fn drop(&mut self) {}
+}
+
+fn log(_: &str) {}
+
+#[finalizer_optional]
+struct Logs(Vec<u8>);
+
+impl Drop for Logs {
+    fn drop(&mut self) {
+        log("dropped"); //~ ERROR `Drop` impl for `#[finalizer_optional]` type `Logs` may do more than free memory
+    }
+}
+
+#[finalizer_optional]
+struct Callback {
+    on_drop: fn(),
+}
+
+impl Drop for Callback {
+    fn drop(&mut self) {
+        (self.on_drop)(); //~ ERROR `Drop` impl for `#[finalizer_optional]` type `Callback` may do more than free memory
+    }
+}
+
+#[finalizer_optional]
+struct MakesNoise(u8);
+
+impl Drop for MakesNoise {
+    fn drop(&mut self) {
+        let _noisy = Noisy;
+    } //~ ERROR `Drop` impl for `#[finalizer_optional]` type `MakesNoise` may do more than free memory
+}
+
+#[finalizer_optional] //~ ERROR `finalizer_optional` attribute should be applied to a struct, enum, or union
+fn not_a_type() {}
+
+// A hand-rolled `Rc`, whose reference count is shared with its clones.
+#[finalizer_optional]
+struct MyRc<T> {
+    ptr: *mut RcBox<T>,
+}
+
+struct RcBox<T> {
+    count: usize,
+    value: T,
+}
+
+impl<T> Drop for MyRc<T> {
+    fn drop(&mut self) {
+        unsafe {
+            (*self.ptr).count -= 1; //~ ERROR `Drop` impl for `#[finalizer_optional]` type `MyRc<T>` may do more than free memory
+            if (*self.ptr).count == 0 {
+                ptr::drop_in_place(&mut (*self.ptr).value);
+                dealloc(self.ptr as *mut u8, Layout::new::<RcBox<T>>());
+            }
+        }
+    }
+}
+
+static mut DROPS: usize = 0;
+
+#[finalizer_optional]
+struct CountsDrops(u8);
+
+impl Drop for CountsDrops {
+    fn drop(&mut self) {
+        unsafe { DROPS += 1 }; //~ ERROR `Drop` impl for `#[finalizer_optional]` type `CountsDrops` may do more than free memory
+    }
+}
+
+// Const functions can write through raw pointers too.
+#[finalizer_optional]
+struct Zeroes(*mut u8);
+
+impl Drop for Zeroes {
+    fn drop(&mut self) {
+        unsafe { ptr::write(self.0, 0) }; //~ ERROR `Drop` impl for `#[finalizer_optional]` type `Zeroes` may do more than free memory
+    }
+}
+
+// The same as `MyRc`, but through a `NonNull`, which can be written through
+// just like a raw pointer.
+#[finalizer_optional]
+struct MyNonNullRc<T> {
+    count: NonNull<usize>,
+    value: NonNull<T>,
+}
+
+impl<T> Drop for MyNonNullRc<T> {
+    fn drop(&mut self) {
+        unsafe { NonNull::write(self.count, 0) }; //~ ERROR `Drop` impl for `#[finalizer_optional]` type `MyNonNullRc<T>` may do more than free memory
+    }
+}
+
+fn main() {}
@@ -0,0 +1,68 @@
+//@ run-pass
+//@ needs-unwind
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::gc::{self, FinalizerPanicInfo, FinalizerPanicPolicy, Gc, GcAllocator};
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time;
+
+struct PanicsOnDrop(usize);
+
+impl Drop for PanicsOnDrop {
+    fn drop(&mut self) {
+        panic!("boom");
+    }
+}
+
+static HOOK_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_COUNT: usize = 10;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+fn foo() {
+    for i in 0..ALLOCATED_COUNT {
+        {
+            let mut _gc = Some(Gc::new(PanicsOnDrop(i)));
+
+            // Zero the root to the GC object.
+            _gc = None;
+        }
+    }
+}
+
+fn counting_hook(info: &FinalizerPanicInfo<'_>) {
+    assert!(info.type_name().ends_with("PanicsOnDrop"));
+    assert_eq!(info.payload().downcast_ref::<&str>(), Some(&"boom"));
+    HOOK_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+    // Replacing the hook from inside the hook must not deadlock.
+    gc::set_finalizer_panic_hook(Box::new(counting_hook));
+}
+
+fn main() {
+    gc::set_finalizer_panic_policy(FinalizerPanicPolicy::Log);
+    gc::set_finalizer_panic_hook(Box::new(counting_hook));
+    assert_eq!(gc::finalizer_panic_policy(), FinalizerPanicPolicy::Hook);
+
+    foo();
+    GcAllocator::force_gc();
+
+    let mut count = HOOK_COUNT.load(atomic::Ordering::Relaxed);
+    let mut sleep_duration = 2;
+    while count < ALLOCATED_COUNT - 1 && sleep_duration <= SLEEP_MAX {
+        // Wait an acceptable amount of time for the finalizer thread to do its work.
+        thread::sleep(time::Duration::from_millis(sleep_duration));
+        sleep_duration = sleep_duration * 2;
+        count = HOOK_COUNT.load(atomic::Ordering::Relaxed);
+    }
+
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(count >= ALLOCATED_COUNT - 1);
+    assert!(count <= ALLOCATED_COUNT);
+
+    // Taking the hook restores the policy which was in use before it was set.
+    assert!(gc::take_finalizer_panic_hook().is_some());
+    assert_eq!(gc::finalizer_panic_policy(), FinalizerPanicPolicy::Log);
+}
@@ -0,0 +1,63 @@
+//@ run-pass
+//@ exec-env:RUST_GC_PROFILE=1
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::gc::{self, Gc, GcAllocator};
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time;
+
+struct Profiled(usize);
+
+impl Drop for Profiled {
+    fn drop(&mut self) {
+        thread::sleep(time::Duration::from_millis(DROP_MILLIS));
+        DROP_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_COUNT: usize = 10;
+static DROP_MILLIS: u64 = 5;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+fn foo() {
+    for i in 0..ALLOCATED_COUNT {
+        {
+            let mut _gc = Some(Gc::new(Profiled(i)));
+            _gc = None;
+        }
+    }
+}
+
+fn main() {
+    // Registrations are recorded on the thread which allocates, so allocate
+    // from another thread to check that its statistics are not lost when it
+    // exits.
+    thread::spawn(foo).join().unwrap();
+    GcAllocator::force_gc();
+
+    let mut count = DROP_COUNT.load(atomic::Ordering::Relaxed);
+    let mut sleep_duration = 2;
+    while count < ALLOCATED_COUNT - 1 && sleep_duration <= SLEEP_MAX {
+        // Wait an acceptable amount of time for the finalizer thread to do its work.
+        thread::sleep(time::Duration::from_millis(sleep_duration));
+        sleep_duration = sleep_duration * 2;
+        count = DROP_COUNT.load(atomic::Ordering::Relaxed);
+    }
+
+    let profile = gc::finalizer_profile();
+    let p = profile.iter().find(|p| p.type_name.ends_with("Profiled")).unwrap();
+    assert_eq!(p.registered, ALLOCATED_COUNT as u64);
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(p.completed >= (ALLOCATED_COUNT - 1) as u64);
+    assert!(p.max_drop_time >= time::Duration::from_millis(DROP_MILLIS));
+    assert!(p.total_drop_time >= p.max_drop_time);
+    // Every finalizer was queued while profiling was enabled, so each one
+    // waited some time to run.
+    assert!(p.max_queue_latency > time::Duration::ZERO);
+    assert!(p.total_queue_latency >= p.max_queue_latency);
+}
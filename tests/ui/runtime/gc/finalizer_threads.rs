@@ -0,0 +1,119 @@
+//@ run-pass
+//@ ignore-emscripten no processes
+//@ ignore-sgx no processes
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::env;
+use std::gc::{FinalizerThreadBuilder, Gc, GcAllocator};
+use std::io::ErrorKind;
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+static THREAD_NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static ALLOCATED_COUNT: usize = 10;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+struct RecordsThread(usize);
+
+impl Drop for RecordsThread {
+    fn drop(&mut self) {
+        let name = thread::current().name().unwrap_or("<unnamed>").to_string();
+        THREAD_NAMES.lock().unwrap().push(name);
+    }
+}
+
+struct SlowDrop(usize);
+
+impl Drop for SlowDrop {
+    fn drop(&mut self) {
+        thread::sleep(Duration::from_millis(200));
+        THREAD_NAMES.lock().unwrap().push("slow".to_string());
+    }
+}
+
+fn wait_for_finalizers(count: usize) -> usize {
+    let mut finalized = THREAD_NAMES.lock().unwrap().len();
+    let mut sleep_duration = 2;
+    while finalized < count && sleep_duration <= SLEEP_MAX {
+        // Wait an acceptable amount of time for the finalizer threads to do their work.
+        thread::sleep(Duration::from_millis(sleep_duration));
+        sleep_duration = sleep_duration * 2;
+        finalized = THREAD_NAMES.lock().unwrap().len();
+    }
+    finalized
+}
+
+fn allocate_garbage() {
+    for i in 0..ALLOCATED_COUNT {
+        let mut _gc = Some(Gc::new(RecordsThread(i)));
+        _gc = None;
+    }
+}
+
+fn allocate_slow_garbage() {
+    for i in 0..2 {
+        let mut _gc = Some(Gc::new(SlowDrop(i)));
+        _gc = None;
+    }
+}
+
+fn threads() {
+    let err = FinalizerThreadBuilder::new().count(0).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = FinalizerThreadBuilder::new().stall_threshold(Duration::ZERO).spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    FinalizerThreadBuilder::new().count(2).name("test-finalizer".to_string()).spawn().unwrap();
+    let err = FinalizerThreadBuilder::new().spawn().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+    allocate_garbage();
+    GcAllocator::force_gc();
+
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(wait_for_finalizers(ALLOCATED_COUNT - 1) >= ALLOCATED_COUNT - 1);
+    let names = THREAD_NAMES.lock().unwrap();
+    let names: HashSet<_> = names.iter().map(|n| n.as_str()).collect();
+    assert!(!names.is_empty());
+    assert!(names.is_subset(&HashSet::from(["test-finalizer-0", "test-finalizer-1"])));
+}
+
+fn watchdog() {
+    FinalizerThreadBuilder::new()
+        .name("test-finalizer".to_string())
+        .stall_threshold(Duration::from_millis(20))
+        .spawn()
+        .unwrap();
+
+    allocate_slow_garbage();
+    GcAllocator::force_gc();
+    assert!(wait_for_finalizers(1) >= 1);
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    match &args[..] {
+        [_, mode] if mode == "threads" => return threads(),
+        [_, mode] if mode == "watchdog" => return watchdog(),
+        _ => {}
+    }
+
+    let me = &args[0];
+    let output = Command::new(me).arg("threads").output().unwrap();
+    assert!(output.status.success(), "{output:#?}");
+
+    // The watchdog reports to stderr, so check it from another process.
+    let output = Command::new(me).arg("watchdog").output().unwrap();
+    assert!(output.status.success(), "{output:#?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("SlowDrop` on thread 'test-finalizer-0' has been running for over 20ms"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,31 @@
+//@ run-pass
+#![feature(gc)]
+#![feature(allocator_api)]
+
+use std::gc::{Gc, GcAllocator};
+
+trait Speak {
+    fn speak(&self) -> usize;
+}
+
+struct Dog(usize);
+
+impl Speak for Dog {
+    fn speak(&self) -> usize {
+        self.0
+    }
+}
+
+impl Drop for Dog {
+    fn drop(&mut self) {
+        println!("Dropping Dog {}", self.0);
+    }
+}
+
+fn main() {
+    // `dyn Speak` says nothing about how `Dog` is dropped, so this is checked
+    // through the concrete type which was unsized into it.
+    let b: Box<dyn Speak, GcAllocator> = Box::new_in(Dog(3), GcAllocator);
+    let gc: Gc<dyn Speak> = Gc::from_box(b);
+    assert_eq!(gc.speak(), 3);
+}
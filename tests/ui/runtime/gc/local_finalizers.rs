@@ -0,0 +1,53 @@
+//@ run-pass
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::gc::{self, Gc, GcAllocator};
+use std::rc::Rc;
+use std::thread;
+use std::time;
+
+// `Rc` is neither `Send` nor `Sync`, so this can only be finalized on the
+// thread which allocated it.
+struct Local(Rc<Cell<usize>>);
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+static ALLOCATED_COUNT: usize = 10;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+fn foo(count: &Rc<Cell<usize>>) {
+    for _ in 0..ALLOCATED_COUNT {
+        {
+            let mut _gc = Some(Gc::new_local(Local(Rc::clone(count))));
+
+            // Zero the root to the GC object.
+            _gc = None;
+        }
+    }
+}
+
+fn main() {
+    let count = Rc::new(Cell::new(0));
+    foo(&count);
+    GcAllocator::force_gc();
+
+    let mut sleep_duration = 2;
+    while count.get() < ALLOCATED_COUNT - 1 && sleep_duration <= SLEEP_MAX {
+        // Finalizers are only ever run when this thread asks for them.
+        gc::run_local_finalizers();
+        thread::sleep(time::Duration::from_millis(sleep_duration));
+        sleep_duration = sleep_duration * 2;
+    }
+
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(count.get() >= ALLOCATED_COUNT - 1);
+    assert!(count.get() <= ALLOCATED_COUNT);
+}
@@ -0,0 +1,96 @@
+//@ run-pass
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::gc::FinalizerOptional;
+use std::mem;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct HasDrop;
+
+impl Drop for HasDrop {
+    fn drop(&mut self) {}
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct HasDropNoFinalize;
+
+impl Drop for HasDropNoFinalize {
+    fn drop(&mut self) {}
+}
+
+unsafe impl FinalizerOptional for HasDropNoFinalize {}
+
+const VEC_DEQUE_TRIVIAL: bool = mem::needs_finalizer::<VecDeque<usize>>();
+const VEC_DEQUE_FINALIZABLE: bool = mem::needs_finalizer::<VecDeque<HasDrop>>();
+const VEC_DEQUE_UNFINALIZABLE: bool = mem::needs_finalizer::<VecDeque<HasDropNoFinalize>>();
+
+const LINKED_LIST_TRIVIAL: bool = mem::needs_finalizer::<LinkedList<usize>>();
+const LINKED_LIST_FINALIZABLE: bool = mem::needs_finalizer::<LinkedList<HasDrop>>();
+const LINKED_LIST_UNFINALIZABLE: bool = mem::needs_finalizer::<LinkedList<HasDropNoFinalize>>();
+
+const BINARY_HEAP_TRIVIAL: bool = mem::needs_finalizer::<BinaryHeap<usize>>();
+const BINARY_HEAP_FINALIZABLE: bool = mem::needs_finalizer::<BinaryHeap<HasDrop>>();
+const BINARY_HEAP_UNFINALIZABLE: bool = mem::needs_finalizer::<BinaryHeap<HasDropNoFinalize>>();
+
+const BTREE_MAP_TRIVIAL: bool = mem::needs_finalizer::<BTreeMap<usize, String>>();
+const BTREE_MAP_FINALIZABLE_KEY: bool = mem::needs_finalizer::<BTreeMap<HasDrop, usize>>();
+const BTREE_MAP_FINALIZABLE_VALUE: bool = mem::needs_finalizer::<BTreeMap<usize, HasDrop>>();
+const BTREE_MAP_UNFINALIZABLE: bool =
+    mem::needs_finalizer::<BTreeMap<HasDropNoFinalize, HasDropNoFinalize>>();
+
+const BTREE_SET_TRIVIAL: bool = mem::needs_finalizer::<BTreeSet<String>>();
+const BTREE_SET_FINALIZABLE: bool = mem::needs_finalizer::<BTreeSet<HasDrop>>();
+const BTREE_SET_UNFINALIZABLE: bool = mem::needs_finalizer::<BTreeSet<HasDropNoFinalize>>();
+
+const HASH_MAP_TRIVIAL: bool = mem::needs_finalizer::<HashMap<usize, String>>();
+const HASH_MAP_FINALIZABLE_KEY: bool = mem::needs_finalizer::<HashMap<HasDrop, usize>>();
+const HASH_MAP_FINALIZABLE_VALUE: bool = mem::needs_finalizer::<HashMap<usize, HasDrop>>();
+const HASH_MAP_UNFINALIZABLE: bool =
+    mem::needs_finalizer::<HashMap<HasDropNoFinalize, HasDropNoFinalize>>();
+
+const HASH_SET_TRIVIAL: bool = mem::needs_finalizer::<HashSet<String>>();
+const HASH_SET_FINALIZABLE: bool = mem::needs_finalizer::<HashSet<HasDrop>>();
+const HASH_SET_UNFINALIZABLE: bool = mem::needs_finalizer::<HashSet<HasDropNoFinalize>>();
+
+const NESTED_TRIVIAL: bool = mem::needs_finalizer::<BTreeMap<String, VecDeque<BTreeSet<usize>>>>();
+const NESTED_FINALIZABLE: bool = mem::needs_finalizer::<BTreeMap<String, VecDeque<BTreeSet<HasDrop>>>>();
+
+fn main() {
+    assert!(!VEC_DEQUE_TRIVIAL);
+    assert!(VEC_DEQUE_FINALIZABLE);
+    assert!(!VEC_DEQUE_UNFINALIZABLE);
+
+    assert!(!LINKED_LIST_TRIVIAL);
+    assert!(LINKED_LIST_FINALIZABLE);
+    assert!(!LINKED_LIST_UNFINALIZABLE);
+
+    assert!(!BINARY_HEAP_TRIVIAL);
+    assert!(BINARY_HEAP_FINALIZABLE);
+    assert!(!BINARY_HEAP_UNFINALIZABLE);
+
+    assert!(!BTREE_MAP_TRIVIAL);
+    assert!(BTREE_MAP_FINALIZABLE_KEY);
+    assert!(BTREE_MAP_FINALIZABLE_VALUE);
+    assert!(!BTREE_MAP_UNFINALIZABLE);
+
+    assert!(!BTREE_SET_TRIVIAL);
+    assert!(BTREE_SET_FINALIZABLE);
+    assert!(!BTREE_SET_UNFINALIZABLE);
+
+    // `HashMap` and `HashSet` are built on `hashbrown`'s `RawTable`, which
+    // doesn't implement `FinalizerOptional`, so they always need a finalizer.
+    assert!(HASH_MAP_TRIVIAL);
+    assert!(HASH_MAP_FINALIZABLE_KEY);
+    assert!(HASH_MAP_FINALIZABLE_VALUE);
+    assert!(HASH_MAP_UNFINALIZABLE);
+
+    assert!(HASH_SET_TRIVIAL);
+    assert!(HASH_SET_FINALIZABLE);
+    assert!(HASH_SET_UNFINALIZABLE);
+
+    assert!(!NESTED_TRIVIAL);
+    assert!(NESTED_FINALIZABLE);
+}
@@ -0,0 +1,43 @@
+//@ run-pass
+#![feature(gc)]
+
+use std::gc::Gc;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+static ALLOCATED: AtomicBool = AtomicBool::new(false);
+
+struct Local(Rc<()>);
+
+impl Drop for Local {
+    fn drop(&mut self) {}
+}
+
+struct AllocOnExit;
+
+impl Drop for AllocOnExit {
+    fn drop(&mut self) {
+        // This thread's queue of local finalizers has already been destroyed,
+        // so this value can never be finalized.
+        let gc = Gc::new_local(Local(Rc::new(())));
+        assert_eq!(Rc::strong_count(&gc.0), 1);
+        ALLOCATED.store(true, Ordering::Relaxed);
+    }
+}
+
+thread_local! {
+    static ON_EXIT: AllocOnExit = AllocOnExit;
+}
+
+fn main() {
+    thread::spawn(|| {
+        // Thread locals are destroyed in the reverse order to which they were
+        // first used, so `ON_EXIT` is destroyed after the queue.
+        ON_EXIT.with(|_| {});
+        let _ = Gc::new_local(Local(Rc::new(())));
+    })
+    .join()
+    .unwrap();
+    assert!(ALLOCATED.load(Ordering::Relaxed));
+}
@@ -0,0 +1,68 @@
+//@ run-pass
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::gc::{Gc, GcAllocator};
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time;
+
+struct HasDrop(usize);
+
+impl Drop for HasDrop {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+static CLEANUP_COUNT: AtomicUsize = AtomicUsize::new(0);
+static CANCELLED_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_COUNT: usize = 10;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+fn foo() {
+    for i in 0..ALLOCATED_COUNT {
+        {
+            let gc = Gc::new(HasDrop(i));
+            // Two actions on the same object, plus one which is cancelled.
+            let _ = Gc::on_collect(&gc, || {
+                CLEANUP_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+            });
+            let _ = Gc::on_collect(&gc, || {
+                CLEANUP_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+            });
+            let cancelled = Gc::on_collect(&gc, || {
+                CANCELLED_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+            });
+            assert!(cancelled.cancel());
+
+            // Zero the root to the GC object.
+            let mut _gc = Some(gc);
+            _gc = None;
+        }
+    }
+}
+
+fn main() {
+    foo();
+    GcAllocator::force_gc();
+
+    let mut count = CLEANUP_COUNT.load(atomic::Ordering::Relaxed);
+    let mut sleep_duration = 2;
+    while count < (ALLOCATED_COUNT - 1) * 2 && sleep_duration <= SLEEP_MAX {
+        // Wait an acceptable amount of time for the finalizer thread to do its work.
+        thread::sleep(time::Duration::from_millis(sleep_duration));
+        sleep_duration = sleep_duration * 2;
+        count = CLEANUP_COUNT.load(atomic::Ordering::Relaxed);
+    }
+
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(count >= (ALLOCATED_COUNT - 1) * 2);
+    assert!(count <= ALLOCATED_COUNT * 2);
+    // The object's own finalizer must still run alongside its cleanup actions.
+    assert!(DROP_COUNT.load(atomic::Ordering::Relaxed) >= ALLOCATED_COUNT - 1);
+    assert_eq!(CANCELLED_COUNT.load(atomic::Ordering::Relaxed), 0);
+}
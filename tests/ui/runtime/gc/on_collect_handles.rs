@@ -0,0 +1,112 @@
+//@ run-pass
+// ignore-tidy-linelength
+#![feature(gc)]
+#![allow(dead_code)]
+
+use std::gc::{self, Cleanup, Gc, GcAllocator};
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Dropped(usize, thread::ThreadId),
+    Cleaned(usize, thread::ThreadId),
+}
+
+static EVENTS: Mutex<Vec<Event>> = Mutex::new(Vec::new());
+static CLEANUP_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_COUNT: usize = 10;
+static SLEEP_MAX: u64 = 8192; // in millis.
+
+// `Rc` is neither `Send` nor `Sync`, so this can only be finalized on the
+// thread which allocated it.
+struct Local(usize, Rc<()>);
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        EVENTS.lock().unwrap().push(Event::Dropped(self.0, thread::current().id()));
+    }
+}
+
+// The handles are kept alive until after the collection, and must not keep
+// their objects alive.
+fn live_handles() -> Vec<Cleanup> {
+    let mut handles = Vec::new();
+    for _ in 0..ALLOCATED_COUNT {
+        {
+            let mut _gc = Some(Gc::new([0u64; 8]));
+            handles.push(Gc::on_collect(_gc.as_ref().unwrap(), || {
+                CLEANUP_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+            }));
+
+            // Zero the root to the GC object.
+            _gc = None;
+        }
+    }
+    handles
+}
+
+fn local_objects() {
+    for i in 0..ALLOCATED_COUNT {
+        {
+            let mut _gc = Some(Gc::new_local(Local(i, Rc::new(()))));
+            let _ = Gc::on_collect(_gc.as_ref().unwrap(), move || {
+                EVENTS.lock().unwrap().push(Event::Cleaned(i, thread::current().id()));
+            });
+
+            // Zero the root to the GC object.
+            _gc = None;
+        }
+    }
+}
+
+fn local_cleanups() -> usize {
+    EVENTS.lock().unwrap().iter().filter(|e| matches!(e, Event::Cleaned(..))).count()
+}
+
+fn main() {
+    let handles = live_handles();
+    local_objects();
+    GcAllocator::force_gc();
+
+    let mut count = CLEANUP_COUNT.load(atomic::Ordering::Relaxed);
+    let mut sleep_duration = 2;
+    while (count < ALLOCATED_COUNT - 1 || local_cleanups() < ALLOCATED_COUNT - 1)
+        && sleep_duration <= SLEEP_MAX
+    {
+        // Wait an acceptable amount of time for the finalizer thread to do its
+        // work, and run the local finalizers it queued for this thread.
+        thread::sleep(time::Duration::from_millis(sleep_duration));
+        gc::run_local_finalizers();
+        sleep_duration = sleep_duration * 2;
+        count = CLEANUP_COUNT.load(atomic::Ordering::Relaxed);
+    }
+
+    // On some platforms, the last object might not be finalised because it's
+    // kept alive by a lingering reference.
+    assert!(count >= ALLOCATED_COUNT - 1);
+    // An action which has already run can no longer be cancelled.
+    let cancelled = handles.into_iter().map(Cleanup::cancel).filter(|c| *c).count();
+    assert!(cancelled <= 1);
+
+    // The actions on local objects run on this thread, after the object's own
+    // drop method.
+    let me = thread::current().id();
+    let events = EVENTS.lock().unwrap();
+    let cleaned: Vec<_> = events
+        .iter()
+        .enumerate()
+        .filter_map(|(pos, e)| match *e {
+            Event::Cleaned(i, id) => Some((pos, i, id)),
+            Event::Dropped(..) => None,
+        })
+        .collect();
+    assert!(cleaned.len() >= ALLOCATED_COUNT - 1);
+    for (pos, i, id) in cleaned {
+        assert_eq!(id, me);
+        assert_eq!(events[..pos].iter().filter(|e| **e == Event::Dropped(i, me)).count(), 1);
+    }
+}
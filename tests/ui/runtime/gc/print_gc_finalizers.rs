@@ -0,0 +1,28 @@
+//@ build-pass
+//@ compile-flags: -Z print-gc-finalizers
+#![feature(gc)]
+
+use std::gc::Gc;
+
+struct Plain(usize);
+
+struct HasDrop(usize);
+
+impl Drop for HasDrop {
+    fn drop(&mut self) {}
+}
+
+struct Outer {
+    inner: Vec<HasDrop>,
+}
+
+fn wrap<T>(value: T) -> Gc<T> {
+    Gc::new(value)
+}
+
+fn main() {
+    Gc::new(Plain(1));
+    Gc::new(Outer { inner: vec![HasDrop(1)] });
+    Gc::new_unfinalizable(HasDrop(2));
+    wrap(HasDrop(3));
+}
@@ -0,0 +1,36 @@
+#![feature(gc)]
+
+use std::gc::{Gc, Unfinalized};
+
+struct HasDrop;
+
+impl Drop for HasDrop {
+    fn drop(&mut self) {}
+}
+
+struct Plain(u8, &'static str);
+
+struct Outer {
+    inner: Vec<HasDrop>,
+}
+
+fn alloc<T: Unfinalized>(value: T) -> Gc<T> {
+    Gc::new(value)
+}
+
+// `Vec<T>` only needs a finalizer if `T` does, which the bound rules out.
+fn alloc_vec<T: Unfinalized>(value: Vec<T>) -> Gc<Vec<T>> {
+    alloc(value)
+}
+
+fn alloc_any<T>(value: T) -> Gc<T> {
+    alloc(value) //~ ERROR `T` needs a finalizer
+}
+
+fn main() {
+    alloc(Plain(1, "one"));
+    alloc(vec![Plain(2, "two")]);
+    alloc_vec(Vec::<HasDrop>::new()); //~ ERROR `HasDrop` needs a finalizer
+    alloc(HasDrop); //~ ERROR `HasDrop` needs a finalizer
+    alloc(Outer { inner: Vec::new() }); //~ ERROR `Outer` needs a finalizer
+}